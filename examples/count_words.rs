@@ -0,0 +1,14 @@
+use mbpe::pre_tokenizers::frequency::WordFrequencies;
+use mbpe::pre_tokenizers::pre_byte_level::SplitPattern;
+use std::io::Error;
+
+fn main() -> Result<(), Error> {
+    let frequencies = WordFrequencies::count_files(
+        &["data/tiny_shakespeare.txt".to_string()],
+        &SplitPattern::Gpt2,
+    )?;
+
+    eprintln!("{} distinct words", frequencies.counts.len());
+
+    frequencies.save("word_frequencies_tiny_shakespeare.tsv")
+}
@@ -5,12 +5,7 @@ use std::io::{BufRead, BufReader, BufWriter, Error, Write};
 fn main() -> Result<(), Error> {
     let model = match mbpe::morfessor::morfessor::decode_model("scripts/semisup_model.proto") {
         Ok(model) => model,
-        Err(_) => {
-            return Err(Error::from(Error::new(
-                std::io::ErrorKind::Other,
-                "Failed to decode model",
-            )))
-        }
+        Err(_) => return Err(Error::other("Failed to decode model")),
     };
 
     let config = mbpe::pre_tokenizers::morfessor::MorfessorConfig {
@@ -21,29 +16,29 @@ fn main() -> Result<(), Error> {
     };
 
     let segmenter = mbpe::pre_tokenizers::morfessor::Morfessor {
-        config: config,
+        config,
         morfessor: model,
     };
 
     let reader = BufReader::new(File::open("data/goldstd_trainset.segmentation.eng")?);
     let mut writer = BufWriter::new(File::create("s0_30_x_2.eng")?);
 
-    if let lines = reader.lines() {
-        for line in lines.flatten() {
-            let compound = line.split("\t").collect::<Vec<&str>>()[0];
-
-            let offsets = segmenter.segment(&compound);
+    for line in reader.lines() {
+        let line = line?;
 
-            let mut parts = vec![];
+        let compound = line.split('\t').collect::<Vec<&str>>()[0];
 
-            for (start, end) in offsets {
-                parts.push(&compound[start..end])
-            }
+        let offsets = segmenter.segment(compound)?.offsets;
 
-            let out = compound.to_string() + "\t" + &parts.join(" ") + "\n";
+        let mut parts = vec![];
 
-            writer.write(out.as_bytes())?;
+        for (start, end) in offsets {
+            parts.push(&compound[start..end])
         }
+
+        let out = compound.to_string() + "\t" + &parts.join(" ") + "\n";
+
+        writer.write_all(out.as_bytes())?;
     }
 
     writer.flush()
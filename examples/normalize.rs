@@ -5,7 +5,7 @@ pub fn main() {
     let normalizer = Sequence::new(vec![
         NormalizerWrapper::from(Strip::new(true, true)),
         NormalizerWrapper::from(Prepend::new(" ".to_string())), // same as BPE add_prefix_space
-        NormalizerWrapper::from(NFC::default()),
+        NormalizerWrapper::from(NFC),
     ]);
 
     let mut normalized = NormalizedString::from("   That's some impressive retrofitting!  ");
@@ -6,8 +6,8 @@ use tokenizers::decoders::byte_level::ByteLevel;
 use tokenizers::models::bpe::BpeTrainerBuilder;
 use tokenizers::models::TrainerWrapper;
 use tokenizers::{
-    tokenizer, AddedToken, DecoderWrapper, Model, ModelWrapper, NormalizerWrapper,
-    PostProcessorWrapper, Result, Tokenizer, TokenizerBuilder,
+    AddedToken, DecoderWrapper, ModelWrapper, NormalizerWrapper, PostProcessorWrapper, Result,
+    Tokenizer, TokenizerBuilder,
 };
 
 fn main() -> Result<()> {
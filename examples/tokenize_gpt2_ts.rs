@@ -1,18 +1,20 @@
 use mbpe::pre_tokenizers::sequence::Sequence;
 use mbpe::pre_tokenizers::tree_split;
+use mbpe::pre_tokenizers::tree_split::TreeSplitConfig;
 use mbpe::pre_tokenizers::PreTokenizerWrapper;
 use tokenizers::decoders::byte_level::ByteLevel;
 use tokenizers::models::bpe::BpeTrainerBuilder;
 use tokenizers::models::TrainerWrapper;
 use tokenizers::{
-    tokenizer, AddedToken, DecoderWrapper, Model, ModelWrapper, NormalizerWrapper,
-    PostProcessorWrapper, Result, Tokenizer, TokenizerBuilder,
+    AddedToken, DecoderWrapper, ModelWrapper, NormalizerWrapper, PostProcessorWrapper, Result,
+    Tokenizer, TokenizerBuilder,
 };
 
 fn main() -> Result<()> {
     let gpt2_tokenizer = Tokenizer::from_pretrained("gpt2", None)?;
 
-    let tree_split = tree_split::new_pre_tokenizer(false, true, ".unimorph/ces/ces");
+    let tree_split =
+        tree_split::new_pre_tokenizer(false, true, &["ces"], TreeSplitConfig::default())?;
 
     let pre_tokenizer = Sequence::new(vec![
         PreTokenizerWrapper::from(tree_split),
@@ -1,11 +1,13 @@
 use mbpe::pre_tokenizers::sequence::Sequence;
 use mbpe::pre_tokenizers::tree_split;
+use mbpe::pre_tokenizers::tree_split::TreeSplitConfig;
 use mbpe::pre_tokenizers::PreTokenizerWrapper;
 use tokenizers::pre_tokenizers::byte_level::ByteLevel;
 use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
 
 pub fn main() {
-    let tree_split = tree_split::new_pre_tokenizer(false, true, ".unimorph/eng/eng");
+    let tree_split =
+        tree_split::new_pre_tokenizer(false, true, &["eng"], TreeSplitConfig::default()).unwrap();
 
     let pre_tokenizer = Sequence::new(vec![
         PreTokenizerWrapper::from(tree_split),
@@ -3,31 +3,37 @@ use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Error, Write};
 
 fn main() -> Result<(), Error> {
-    let mut unimorph = mbpe::unimorph::unimorph::Unimorph::new();
+    let mut registry = mbpe::unimorph::registry::UnimorphRegistry::from_env();
 
-    unimorph.init("/Users/jonas/.unimorph/eng/eng")?;
+    let report = registry.load("eng")?;
 
-    let segmenter = mbpe::pre_tokenizers::tree_split::TreeSplit { unimorph };
+    eprint!("{}", report);
+
+    let segmenter = mbpe::pre_tokenizers::tree_split::TreeSplit::new(
+        registry,
+        vec!["eng".to_string()],
+        mbpe::pre_tokenizers::tree_split::TreeSplitConfig::default(),
+    );
 
     let reader = BufReader::new(File::open("data/goldstd_trainset.segmentation.eng")?);
     let mut writer = BufWriter::new(File::create("tree_split.eng")?);
 
-    if let lines = reader.lines() {
-        for line in lines.flatten() {
-            let compound = line.split("\t").collect::<Vec<&str>>()[0];
-
-            let offsets = segmenter.segment(&compound);
+    for line in reader.lines() {
+        let line = line?;
 
-            let mut parts = vec![];
+        let compound = line.split('\t').collect::<Vec<&str>>()[0];
 
-            for (start, end) in offsets {
-                parts.push(&compound[start..end])
-            }
+        let offsets = segmenter.segment(compound)?.offsets;
 
-            let out = compound.to_string() + "\t" + &parts.join(" ") + "\n";
+        let mut parts = vec![];
 
-            writer.write(out.as_bytes())?;
+        for (start, end) in offsets {
+            parts.push(&compound[start..end])
         }
+
+        let out = compound.to_string() + "\t" + &parts.join(" ") + "\n";
+
+        writer.write_all(out.as_bytes())?;
     }
 
     writer.flush()
@@ -0,0 +1,19 @@
+use std::io::Error;
+
+fn main() -> Result<(), Error> {
+    let mut registry = mbpe::unimorph::registry::UnimorphRegistry::from_env();
+
+    let report = registry.load("eng")?;
+
+    eprint!("{}", report);
+
+    let segmenter = mbpe::pre_tokenizers::tree_split::TreeSplit::new(
+        registry,
+        vec!["eng".to_string()],
+        mbpe::pre_tokenizers::tree_split::TreeSplitConfig::default(),
+    );
+
+    let table = mbpe::pre_tokenizers::boundary_table::BoundaryTable::build(&segmenter);
+
+    table.save("tree_split_table.eng.json")
+}
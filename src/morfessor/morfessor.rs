@@ -1,13 +1,13 @@
 use std::fs;
+use std::io::{Error, ErrorKind};
 use std::path::Path;
 
 use bytes::Bytes;
 use prost::{DecodeError, Message};
 
-use serde::{Deserialize, Serialize};
-
 use crate::utils::offsets::unicode_scalar_bounds;
 
+#[allow(clippy::module_inception)]
 pub mod morfessor {
     include!(concat!(env!("OUT_DIR"), "/morfessor.rs"));
 }
@@ -21,7 +21,7 @@ pub fn viterbi_segment(
     compound: &str,
     add_count: f64,
     max_len: usize,
-) -> (Vec<String>, f64) {
+) -> Result<(Vec<String>, f64), Error> {
     let compound_length = unicode_scalar_bounds(compound).len();
 
     let mut grid: Vec<(f64, Option<usize>)> = vec![(0.0, None)];
@@ -39,7 +39,7 @@ pub fn viterbi_segment(
 
     let bounds = unicode_scalar_bounds(compound);
 
-    let mut bounds_upper = bounds.clone();
+    let bounds_upper = bounds.clone();
     let mut bounds_lower = bounds.clone();
 
     bounds_lower.pop();
@@ -78,10 +78,13 @@ pub fn viterbi_segment(
             if let Some(analyses) = model.analyses.get(construction) {
                 if analyses.splitloc.is_empty() || analyses.splitloc[0] == 0 {
                     if analyses.count <= 0 {
-                        panic!(
-                            "Construction count of '{}' is {}",
-                            construction, analyses.count
-                        );
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Construction count of '{}' is {}",
+                                construction, analyses.count
+                            ),
+                        ));
                     }
 
                     cost += log_tokens - (analyses.count as f64 + add_count).ln();
@@ -134,7 +137,10 @@ pub fn viterbi_segment(
         }
 
         if best_path.is_none() {
-            panic!("No best path");
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("No best path for '{}'", compound),
+            ));
         }
 
         // pad grid to account for multibyte characters
@@ -149,14 +155,17 @@ pub fn viterbi_segment(
 
     let mut constructions: Vec<String> = Vec::new();
 
-    if grid.len() != compound.as_bytes().len() + 1 {
-        panic!("Invalid grid length");
+    if grid.len() != compound.len() + 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid grid length for '{}'", compound),
+        ));
     }
 
     let mut cost = grid[grid.len() - 1].0;
     let mut path = grid[grid.len() - 1].1;
 
-    let mut last_t = compound.as_bytes().len();
+    let mut last_t = compound.len();
 
     while let Some(t) = path {
         constructions.push(compound[t..last_t].to_string());
@@ -168,11 +177,14 @@ pub fn viterbi_segment(
 
     cost += (corpus_tokens + corpus_boundaries).ln() - corpus_boundaries.ln();
 
-    if constructions.len() == 0 {
-        panic!("No constructions");
+    if constructions.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("No constructions for '{}'", compound),
+        ));
     }
 
-    return (constructions, cost);
+    Ok((constructions, cost))
 }
 
 pub fn get_code_length(lexicon_encoding: &morfessor::LexiconEncoding, construction: &str) -> f64 {
@@ -200,6 +212,7 @@ pub fn get_code_length(lexicon_encoding: &morfessor::LexiconEncoding, constructi
     cost
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -229,7 +242,7 @@ mod tests {
     fn test_viterbi_segment() {
         let model = decode_model("scripts/unsup_model.proto").unwrap();
 
-        let (segments, score) = viterbi_segment(&model, "unfoobared", 0.0, 30);
+        let (segments, score) = viterbi_segment(&model, "unfoobared", 0.0, 30).unwrap();
 
         assert_eq!(segments, vec!["un", "foo", "bar", "ed"]);
         assert_eq!(score, 32.684465337620665);
@@ -239,7 +252,7 @@ mod tests {
     fn test_viterbi_segment_composed() {
         let model = decode_model("scripts/unsup_model.proto").unwrap();
 
-        let (segments, score) = viterbi_segment(&model, "brul\u{00E9}e", 0.0, 30);
+        let (segments, score) = viterbi_segment(&model, "brul\u{00E9}e", 0.0, 30).unwrap();
 
         assert_eq!(segments, vec!["bru", "l", "\u{00E9}", "e"]);
         assert_eq!(score, 109.47779723820601);
@@ -249,7 +262,7 @@ mod tests {
     fn test_viterbi_segment_decomposed() {
         let model = decode_model("scripts/unsup_model.proto").unwrap();
 
-        let (segments, score) = viterbi_segment(&model, "brul\u{0065}\u{0301}e", 0.0, 30);
+        let (segments, score) = viterbi_segment(&model, "brul\u{0065}\u{0301}e", 0.0, 30).unwrap();
 
         assert_eq!(segments, vec!["brul\u{0065}", "\u{0301}", "e"]);
         assert_eq!(score, 118.92118396646775);
@@ -259,7 +272,7 @@ mod tests {
     fn test_viterbi_segment_max_len() {
         let model = decode_model("scripts/unsup_model.proto").unwrap();
 
-        let (segments, score) = viterbi_segment(&model, "unsupervised", 0.0, 5);
+        let (segments, score) = viterbi_segment(&model, "unsupervised", 0.0, 5).unwrap();
 
         assert_eq!(segments, vec!["un", "super", "vis", "ed"]);
         assert_eq!(score, 29.684031672881893);
@@ -0,0 +1,189 @@
+use std::io::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+use crate::utils::offsets::bounds_to_offsets;
+
+pub fn new_pre_tokenizer(
+    add_prefix_space: bool,
+    use_regex: bool,
+    config: AffixStripConfig,
+) -> PreByteLevel {
+    PreByteLevel::new(
+        add_prefix_space,
+        use_regex,
+        SegmenterWrapper::AffixStrip(AffixStrip::new(config)),
+    )
+}
+
+// unlike TreeSplit and Morfessor the segmenter is fully described by its config
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct AffixStrip {
+    pub config: AffixStripConfig,
+}
+
+// affixes are matched longest first, ties are resolved by list order
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct AffixStripConfig {
+    pub prefixes: Vec<String>,
+    pub suffixes: Vec<String>,
+    // minimum number of characters remaining after stripping an affix, at least one
+    pub min_stem_length: usize,
+    pub max_affixes: usize,
+}
+
+impl Default for AffixStripConfig {
+    fn default() -> Self {
+        AffixStripConfig {
+            prefixes: vec![],
+            suffixes: vec![],
+            min_stem_length: 3,
+            max_affixes: 3,
+        }
+    }
+}
+
+impl AffixStrip {
+    pub fn new(config: AffixStripConfig) -> Self {
+        AffixStrip { config }
+    }
+
+    // longest affix leaving a stem of sufficient length
+    fn longest<'a, F>(&self, affixes: &'a [String], stem: &str, matches: F) -> Option<&'a String>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut longest: Option<&String> = None;
+
+        for affix in affixes {
+            if affix.is_empty() || !matches(affix) {
+                continue;
+            }
+
+            // an affix never takes the whole stem, segments would be empty otherwise
+            if stem.chars().count() - affix.chars().count() < self.config.min_stem_length.max(1) {
+                continue;
+            }
+
+            if longest.is_none_or(|other| affix.len() > other.len()) {
+                longest = Some(affix);
+            }
+        }
+
+        longest
+    }
+}
+
+impl Segmenter for AffixStrip {
+    // prefixes and suffixes are stripped alternately until no affix matches
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        let mut start = 0;
+        let mut end = message.len();
+
+        let mut bounds = vec![];
+
+        loop {
+            let mut stripped = false;
+
+            if bounds.len() < self.config.max_affixes {
+                let stem = &message[start..end];
+
+                if let Some(prefix) =
+                    self.longest(&self.config.prefixes, stem, |affix| stem.starts_with(affix))
+                {
+                    start += prefix.len();
+
+                    bounds.push(start);
+
+                    stripped = true;
+                }
+            }
+
+            if bounds.len() < self.config.max_affixes {
+                let stem = &message[start..end];
+
+                if let Some(suffix) =
+                    self.longest(&self.config.suffixes, stem, |affix| stem.ends_with(affix))
+                {
+                    end -= suffix.len();
+
+                    bounds.push(end);
+
+                    stripped = true;
+                }
+            }
+
+            if !stripped {
+                break;
+            }
+        }
+
+        bounds.sort();
+
+        Ok(Segmentation::new(
+            "affix_strip",
+            bounds_to_offsets(&bounds, message.len()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn affix_strip(min_stem_length: usize, max_affixes: usize) -> AffixStrip {
+        AffixStrip::new(AffixStripConfig {
+            prefixes: vec!["un".to_string(), "re".to_string()],
+            suffixes: vec!["s".to_string(), "es".to_string(), "ness".to_string()],
+            min_stem_length,
+            max_affixes,
+        })
+    }
+
+    #[test]
+    fn test_segment() {
+        assert_eq!(
+            affix_strip(3, 4).segment("unkindnesses").unwrap().offsets,
+            vec![(0, 2), (2, 6), (6, 10), (10, 12)]
+        );
+        assert_eq!(
+            affix_strip(3, 2).segment("unkindnesses").unwrap().offsets,
+            vec![(0, 2), (2, 10), (10, 12)]
+        );
+        // kindness is too short a stem, the shorter suffix is stripped instead
+        assert_eq!(
+            affix_strip(9, 4).segment("unkindnesses").unwrap().offsets,
+            vec![(0, 2), (2, 11), (11, 12)]
+        );
+        assert_eq!(
+            affix_strip(3, 4).segment("kind").unwrap().offsets,
+            vec![(0, 4)]
+        );
+    }
+
+    #[test]
+    fn test_affix_only() {
+        assert_eq!(
+            affix_strip(0, 4).segment("s").unwrap().offsets,
+            vec![(0, 1)]
+        );
+        assert_eq!(
+            affix_strip(0, 4).segment("unes").unwrap().offsets,
+            vec![(0, 2), (2, 3), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let segmenter = SegmenterWrapper::AffixStrip(affix_strip(3, 4));
+
+        let json = serde_json::to_string(&segmenter).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<SegmenterWrapper>(&json).unwrap(),
+            segmenter
+        );
+    }
+}
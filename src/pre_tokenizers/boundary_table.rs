@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error};
+
+use serde::{Deserialize, Serialize};
+
+use tokenizers::utils::parallelism::MaybeParallelRefIterator;
+
+use crate::pre_tokenizers::tree_split::{bounds_to_original_offsets, TreeSplit, TreeSplitConfig};
+use crate::unimorph::normalizer::LookupNormalizer;
+
+// boundaries of every dictionary form computed once by TreeSplit
+// segmentation at runtime is a single lookup per language
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct BoundaryTable {
+    // configuration the boundaries were computed with
+    pub config: TreeSplitConfig,
+    languages: HashMap<String, LanguageTable>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+struct LanguageTable {
+    normalizer: LookupNormalizer,
+    // normalized form -> character bounds
+    // forms without bounds are kept so that language priority matches the registry lookup
+    bounds: HashMap<String, Vec<usize>>,
+}
+
+impl BoundaryTable {
+    pub fn build(tree_split: &TreeSplit) -> Self {
+        let mut languages = HashMap::new();
+
+        for language in &tree_split.languages {
+            let unimorph = match tree_split.registry.get(language) {
+                Some(unimorph) => unimorph,
+                None => continue,
+            };
+
+            let forms = unimorph.forms().collect::<Vec<&String>>();
+
+            let bounds = forms
+                .maybe_par_iter()
+                .map(|form| (form.to_string(), tree_split.form_bounds(unimorph, form)))
+                .collect::<HashMap<String, Vec<usize>>>();
+
+            languages.insert(
+                language.clone(),
+                LanguageTable {
+                    normalizer: unimorph.normalizer().clone(),
+                    bounds,
+                },
+            );
+        }
+
+        BoundaryTable {
+            config: tree_split.config.clone(),
+            languages,
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(path)?);
+
+        serde_json::to_writer(writer, self)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn contains(&self, language: &str) -> bool {
+        self.languages.contains_key(language)
+    }
+
+    // character bounds of a form, None for forms missing from the dictionary
+    pub fn get(&self, language: &str, form: &str) -> Option<&Vec<usize>> {
+        let table = self.languages.get(language)?;
+
+        if table.normalizer.is_identity() {
+            return table.bounds.get(form);
+        }
+
+        table.bounds.get(table.normalizer.normalize(form).get())
+    }
+
+    // the first language in the given order that knows the form is used
+    // None for forms unknown to all languages
+    pub fn segment(
+        &self,
+        languages: &[String],
+        message: &str,
+    ) -> Result<Option<Vec<(usize, usize)>>, Error> {
+        for language in languages {
+            let table = match self.languages.get(language) {
+                Some(table) => table,
+                None => continue,
+            };
+
+            let normalized = table.normalizer.normalize(message);
+
+            if let Some(bounds) = table.bounds.get(normalized.get()) {
+                return Ok(Some(bounds_to_original_offsets(&normalized, bounds)?));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::segmenter::Segmenter;
+    use crate::pre_tokenizers::test_utils::{tree_split, write_temp};
+    use crate::pre_tokenizers::tree_split::Direction;
+
+    fn ces() -> TreeSplit {
+        tree_split("ces", "ces_afghansky.tsv", &["ces"], Direction::Prefix)
+    }
+
+    #[test]
+    fn test_build() {
+        let tree_split = ces();
+
+        let table = TreeSplit::from_table(
+            BoundaryTable::build(&tree_split),
+            tree_split.languages.clone(),
+        );
+
+        let unimorph = tree_split.registry.get("ces").unwrap();
+
+        for form in unimorph.forms() {
+            assert_eq!(
+                table.segment(form).unwrap(),
+                tree_split.segment(form).unwrap()
+            );
+        }
+
+        assert!(!table.segment("foo").unwrap().known);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let table = BoundaryTable::build(&ces());
+
+        let path = write_temp("boundary_table.json", "");
+        let path = path.to_str().unwrap();
+
+        table.save(path).unwrap();
+
+        assert_eq!(BoundaryTable::load(path).unwrap(), table);
+    }
+}
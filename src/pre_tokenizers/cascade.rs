@@ -0,0 +1,195 @@
+use std::io::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+
+// e.g. compound splitting followed by Morfessor within each compound part
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Cascade {
+    pub coarse: Box<SegmenterWrapper>,
+    pub fine: Box<SegmenterWrapper>,
+    // number of times the fine segmenter is applied to the resulting pieces
+    pub depth: usize,
+}
+
+impl Cascade {
+    pub fn new(coarse: SegmenterWrapper, fine: SegmenterWrapper, depth: usize) -> Self {
+        Cascade {
+            coarse: Box::new(coarse),
+            fine: Box::new(fine),
+            depth,
+        }
+    }
+
+    // applies the fine segmenter to each piece and shifts offsets back into the parent word
+    // boundaries between pieces keep their scores, the segmentation needs to be valid
+    // and thus hold a score for each boundary
+    fn refine(&self, message: &str, segmentation: &Segmentation) -> Result<Segmentation, Error> {
+        let mut refined = Segmentation {
+            offsets: vec![],
+            scores: vec![],
+            known: segmentation.known,
+            source: "cascade".to_string(),
+        };
+
+        for (i, (start, end)) in segmentation.offsets.iter().enumerate() {
+            if i > 0 {
+                refined.scores.push(segmentation.scores[i - 1]);
+            }
+
+            let fragment = &message[*start..*end];
+
+            let piece = self.fine.segment(fragment)?;
+
+            piece.validate(fragment)?;
+
+            if !piece.known {
+                refined.offsets.push((*start, *end));
+
+                continue;
+            }
+
+            refined.known = true;
+
+            refined
+                .offsets
+                .extend(piece.offsets.iter().map(|(s, e)| (start + s, start + e)));
+            refined.scores.extend(piece.scores);
+        }
+
+        Ok(refined)
+    }
+}
+
+impl Segmenter for Cascade {
+    // unknown only if unknown to the coarse segmenter and to the fine segmenter at every level
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        let mut segmentation = self.coarse.segment(message)?;
+
+        segmentation.validate(message)?;
+
+        segmentation.source = "cascade".to_string();
+
+        for _ in 0..self.depth {
+            let refined = self.refine(message, &segmentation)?;
+
+            if refined.offsets == segmentation.offsets {
+                segmentation.known = refined.known;
+
+                break;
+            }
+
+            segmentation = refined;
+        }
+
+        Ok(segmentation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::custom::CustomSegmenter;
+    use crate::pre_tokenizers::test_utils::{tree_split, write_temp, Halves};
+    use crate::pre_tokenizers::tree_split::Direction;
+
+    // splits ASCII words in the middle but reports them as unknown and without scores
+    #[derive(Deserialize, Serialize)]
+    struct UnknownHalves;
+
+    impl Segmenter for UnknownHalves {
+        fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+            let half = message.len() / 2;
+
+            Ok(Segmentation {
+                offsets: vec![(0, half), (half, message.len())],
+                scores: vec![],
+                known: false,
+                source: "unknown_halves".to_string(),
+            })
+        }
+    }
+
+    impl CustomSegmenter for UnknownHalves {
+        fn type_name(&self) -> &str {
+            "unknown_halves"
+        }
+
+        fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+            serde_json::to_value(self)
+        }
+    }
+
+    fn dictionary(language: &str, content: &str) -> SegmenterWrapper {
+        let path = write_temp(&format!("cascade_{}.tsv", language), content);
+
+        SegmenterWrapper::TreeSplit(tree_split(language, path, &[language], Direction::Prefix))
+    }
+
+    // haus|doors as a compound, door|s and do|or as inflections
+    fn cascade(depth: usize) -> Cascade {
+        Cascade::new(
+            dictionary("deu", "hausdoors\thaus\tN\nhausdoors\thausdoors\tN;PL"),
+            dictionary(
+                "eng",
+                "door\tdoor\tN;SG\ndoor\tdoors\tN;PL\ndo\tdo\tV\ndo\tdoor\tV;AGT",
+            ),
+            depth,
+        )
+    }
+
+    #[test]
+    fn test_depth() {
+        let offsets = |depth: usize| cascade(depth).segment("hausdoors").unwrap().offsets;
+
+        assert_eq!(offsets(0), vec![(0, 4), (4, 9)]);
+        assert_eq!(offsets(1), vec![(0, 4), (4, 8), (8, 9)]);
+        assert_eq!(offsets(2), vec![(0, 4), (4, 6), (6, 8), (8, 9)]);
+        assert_eq!(offsets(3), offsets(2));
+    }
+
+    #[test]
+    fn test_unknown() {
+        // unknown to the coarse segmenter but known to the fine segmenter
+        let segmentation = cascade(1).segment("doors").unwrap();
+
+        assert_eq!(segmentation.offsets, vec![(0, 4), (4, 5)]);
+        assert!(segmentation.known);
+        assert!(!cascade(1).segment("foo").unwrap().known);
+    }
+
+    #[test]
+    fn test_invalid() {
+        // "ačas" is split within "č"
+        let coarse = Cascade::new(
+            SegmenterWrapper::from(Halves),
+            dictionary("eng", "door\tdoor\tN;SG\ndoor\tdoors\tN;PL"),
+            1,
+        );
+
+        assert!(coarse.segment("ačas").is_err());
+
+        let fine = Cascade::new(
+            dictionary("deu", "hausdoors\thaus\tN\nhausdoors\thausdoors\tN;PL"),
+            SegmenterWrapper::from(Halves),
+            1,
+        );
+
+        assert!(fine.segment("ačas").is_err());
+        assert_eq!(fine.segment("abcd").unwrap().offsets, vec![(0, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn test_unknown_split() {
+        let cascade = Cascade::new(
+            SegmenterWrapper::from(UnknownHalves),
+            dictionary("eng", "door\tdoor\tN;SG\ndoor\tdoors\tN;PL"),
+            1,
+        );
+
+        let error = cascade.segment("doorsdoors").unwrap_err();
+
+        assert!(error.to_string().contains("expected 1 scores, found 0"));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+
+// segmenters defined outside of this crate
+// implementations are registered by name to be deserialized, see register_segmenter
+pub trait CustomSegmenter: Segmenter + Send + Sync {
+    // name the segmenter type is registered under
+    fn type_name(&self) -> &str;
+
+    // usually serde_json::to_value(self)
+    fn to_json(&self) -> serde_json::Result<Value>;
+}
+
+type Constructor = Box<dyn Fn(Value) -> serde_json::Result<Custom> + Send + Sync>;
+
+lazy_static! {
+    static ref REGISTRY: RwLock<HashMap<String, Constructor>> = RwLock::new(HashMap::new());
+}
+
+// needs to be called before deserializing tokenizers containing segmenters of the given type
+pub fn register_segmenter<T>(type_name: &str)
+where
+    T: CustomSegmenter + DeserializeOwned + 'static,
+{
+    REGISTRY.write().unwrap().insert(
+        type_name.to_string(),
+        Box::new(|value| Ok(Custom::new(serde_json::from_value::<T>(value)?))),
+    );
+}
+
+pub fn is_registered(type_name: &str) -> bool {
+    REGISTRY.read().unwrap().contains_key(type_name)
+}
+
+#[derive(Clone)]
+pub struct Custom {
+    segmenter: Arc<dyn CustomSegmenter>,
+}
+
+impl Custom {
+    pub fn new<T: CustomSegmenter + 'static>(segmenter: T) -> Self {
+        Custom {
+            segmenter: Arc::new(segmenter),
+        }
+    }
+
+    pub fn type_name(&self) -> &str {
+        self.segmenter.type_name()
+    }
+}
+
+// serialized as {"segmenter": <type name>, "config": <segmenter>}
+#[derive(Deserialize, Serialize)]
+struct CustomRepr {
+    segmenter: String,
+    config: Value,
+}
+
+impl Serialize for Custom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CustomRepr {
+            segmenter: self.type_name().to_string(),
+            config: self
+                .segmenter
+                .to_json()
+                .map_err(serde::ser::Error::custom)?,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Custom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = CustomRepr::deserialize(deserializer)?;
+
+        let registry = REGISTRY.read().unwrap();
+
+        let constructor = registry.get(&repr.segmenter).ok_or_else(|| {
+            D::Error::custom(format!("unregistered segmenter '{}'", repr.segmenter))
+        })?;
+
+        constructor(repr.config).map_err(D::Error::custom)
+    }
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("segmenter", &self.type_name())
+            .field("config", &self.segmenter.to_json().ok())
+            .finish()
+    }
+}
+
+// segmenters are equal if they are of the same type and share the same configuration
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_name() == other.type_name()
+            && self.segmenter.to_json().ok() == other.segmenter.to_json().ok()
+    }
+}
+
+impl Segmenter for Custom {
+    fn segment(&self, message: &str) -> Result<Segmentation, std::io::Error> {
+        self.segmenter.segment(message)
+    }
+}
+
+impl<T: CustomSegmenter + 'static> From<T> for SegmenterWrapper {
+    fn from(from: T) -> Self {
+        SegmenterWrapper::Custom(Custom::new(from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::fallback::{Fallback, FallbackTrigger};
+    use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    // splits words into chunks of fixed length
+    #[derive(Deserialize, Serialize)]
+    struct Chunks {
+        size: usize,
+    }
+
+    impl Segmenter for Chunks {
+        fn segment(&self, message: &str) -> Result<Segmentation, std::io::Error> {
+            Ok(Segmentation::new(
+                "chunks",
+                (0..message.len())
+                    .step_by(self.size)
+                    .map(|start| (start, (start + self.size).min(message.len())))
+                    .collect(),
+            ))
+        }
+    }
+
+    impl CustomSegmenter for Chunks {
+        fn type_name(&self) -> &str {
+            "chunks"
+        }
+
+        fn to_json(&self) -> serde_json::Result<Value> {
+            serde_json::to_value(self)
+        }
+    }
+
+    #[test]
+    fn test_segment() {
+        let segmenter = SegmenterWrapper::from(Chunks { size: 2 });
+
+        assert_eq!(
+            segmenter.segment("abcde").unwrap().offsets,
+            vec![(0, 2), (2, 4), (4, 5)]
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let pre_tokenizer =
+            PreTokenizerWrapper::from(PreByteLevel::new(true, true, Chunks { size: 2 }));
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.contains("{\"segmenter\":\"chunks\",\"config\":{\"size\":2}}"));
+
+        register_segmenter::<Chunks>("chunks");
+
+        assert!(is_registered("chunks"));
+        assert_eq!(
+            serde_json::from_str::<PreTokenizerWrapper>(&json).unwrap(),
+            pre_tokenizer
+        );
+
+        let json = json.replace("\"chunks\"", "\"unregistered\"");
+
+        let error = serde_json::from_str::<PreTokenizerWrapper>(&json).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("unregistered segmenter 'unregistered'"));
+    }
+
+    #[test]
+    fn test_nested() {
+        let segmenter = SegmenterWrapper::Fallback(Fallback::new(
+            SegmenterWrapper::from(Chunks { size: 2 }),
+            SegmenterWrapper::from(Chunks { size: 3 }),
+            FallbackTrigger::Unsplit,
+        ));
+
+        let json = serde_json::to_string(&segmenter)
+            .unwrap()
+            .replace("\"chunks\"", "\"nested\"");
+
+        let error = serde_json::from_str::<SegmenterWrapper>(&json).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("unregistered segmenter 'nested'"));
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+use crate::unimorph::loader::{IngestionReport, LoadMode};
+use crate::utils::offsets::bounds_to_offsets;
+
+// segmentation lexicons with one word per line followed by its analyses
+// e.g. abacus's<TAB>abacus:abacus_N 's:+GEN (Morpho Challenge)
+
+pub fn new_pre_tokenizer(
+    add_prefix_space: bool,
+    use_regex: bool,
+    lexicon_path: &str,
+    config: DictionaryConfig,
+) -> Result<PreByteLevel, Error> {
+    let mut segmenter = Dictionary::new(config);
+
+    segmenter.load(lexicon_path)?;
+
+    Ok(PreByteLevel::new(
+        add_prefix_space,
+        use_regex,
+        SegmenterWrapper::Dictionary(segmenter),
+    ))
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MultiAnalysis {
+    // first valid analysis in the lexicon
+    #[default]
+    First,
+    // valid analysis with the fewest morphs
+    Fewest,
+    // boundaries of all valid analyses
+    Union,
+    // boundaries shared by all valid analyses
+    Intersection,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DictionaryConfig {
+    pub has_headers: bool,
+    pub mode: LoadMode,
+    // separates alternative analyses of a word, a single analysis per word if None
+    pub analysis_separator: Option<String>,
+    pub morph_separator: String,
+    // separates a morph from its label, everything after the separator is ignored
+    pub label_separator: Option<String>,
+    pub multi_analysis: MultiAnalysis,
+}
+
+impl Default for DictionaryConfig {
+    fn default() -> Self {
+        DictionaryConfig {
+            has_headers: false,
+            mode: LoadMode::Lenient,
+            analysis_separator: None,
+            morph_separator: " ".to_string(),
+            label_separator: None,
+            multi_analysis: MultiAnalysis::First,
+        }
+    }
+}
+
+impl DictionaryConfig {
+    // abacus's<TAB>abacus:abacus_N 's:+GEN, ...
+    pub fn morpho_challenge() -> Self {
+        DictionaryConfig {
+            analysis_separator: Some(", ".to_string()),
+            label_separator: Some(":".to_string()),
+            ..DictionaryConfig::default()
+        }
+    }
+
+    // unfolded<TAB>un @@fold @@ed<TAB>110
+    pub fn sigmorphon() -> Self {
+        DictionaryConfig {
+            morph_separator: " @@".to_string(),
+            ..DictionaryConfig::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Dictionary {
+    pub config: DictionaryConfig,
+    #[serde(skip_deserializing, skip_serializing)]
+    pub lexicon: HashMap<String, Vec<usize>>, // word -> byte bounds
+}
+
+impl Dictionary {
+    pub fn new(config: DictionaryConfig) -> Self {
+        Dictionary {
+            config,
+            lexicon: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, lexicon: &str) -> Result<IngestionReport, Error> {
+        let file = File::open(lexicon)?;
+
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(self.config.has_headers)
+            .flexible(true)
+            .quoting(false)
+            .from_reader(file);
+
+        let mut report = IngestionReport::default();
+
+        for result in rdr.records() {
+            let record = result?;
+
+            let line = record.position().map_or(0, |position| position.line());
+
+            report.rows += 1;
+
+            let analyses = if record.len() < 2 {
+                Err(format!("expected 2 fields, found {}", record.len()))
+            } else if record[0].is_empty() {
+                Err("empty word".to_string())
+            } else {
+                self.analyses(&record[0], &record[1])
+            };
+
+            let analyses = match analyses {
+                Ok(analyses) => analyses,
+                Err(reason) => {
+                    if self.config.mode == LoadMode::Strict {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("{}: line {}: {}", lexicon, line, reason),
+                        ));
+                    }
+
+                    report.skipped.push((line, reason));
+
+                    continue;
+                }
+            };
+
+            if self.lexicon.contains_key(&record[0]) {
+                report.duplicates.push(line);
+
+                continue;
+            }
+
+            self.lexicon
+                .insert(record[0].to_string(), self.resolve(analyses));
+
+            report.entries += 1;
+        }
+
+        Ok(report)
+    }
+
+    // byte bounds of all analyses, invalid analyses are rejected in strict mode and dropped otherwise
+    fn analyses(&self, word: &str, field: &str) -> Result<Vec<Vec<usize>>, String> {
+        let analyses = match &self.config.analysis_separator {
+            Some(separator) => field.split(separator.as_str()).collect::<Vec<&str>>(),
+            None => vec![field],
+        };
+
+        let mut result = vec![];
+        let mut reason = "no analysis".to_string();
+
+        for analysis in analyses {
+            match self.bounds(word, analysis) {
+                Ok(bounds) => result.push(bounds),
+                Err(invalid) if self.config.mode == LoadMode::Strict => return Err(invalid),
+                Err(invalid) => reason = invalid,
+            }
+        }
+
+        if result.is_empty() {
+            return Err(reason);
+        }
+
+        Ok(result)
+    }
+
+    // morphs need to concatenate to the word
+    fn bounds(&self, word: &str, analysis: &str) -> Result<Vec<usize>, String> {
+        let morphs = analysis
+            .split(self.config.morph_separator.as_str())
+            .map(|morph| match &self.config.label_separator {
+                Some(separator) => morph.split(separator.as_str()).next().unwrap_or(""),
+                None => morph,
+            })
+            .filter(|morph| !morph.is_empty())
+            .collect::<Vec<&str>>();
+
+        if morphs.concat() != word {
+            return Err(format!(
+                "morphs '{}' do not concatenate to '{}'",
+                morphs.join(" "),
+                word
+            ));
+        }
+
+        let mut bounds = vec![];
+        let mut position = 0;
+
+        for morph in &morphs[..morphs.len() - 1] {
+            position += morph.len();
+
+            bounds.push(position);
+        }
+
+        Ok(bounds)
+    }
+
+    fn resolve(&self, mut analyses: Vec<Vec<usize>>) -> Vec<usize> {
+        let mut bounds = match self.config.multi_analysis {
+            MultiAnalysis::First => analyses.swap_remove(0),
+            MultiAnalysis::Fewest => analyses.iter().min_by_key(|a| a.len()).unwrap().clone(),
+            MultiAnalysis::Union => analyses.concat(),
+            MultiAnalysis::Intersection => analyses[0]
+                .iter()
+                .filter(|bound| analyses.iter().all(|other| other.contains(bound)))
+                .cloned()
+                .collect(),
+        };
+
+        bounds.sort();
+        bounds.dedup();
+
+        bounds
+    }
+}
+
+impl Segmenter for Dictionary {
+    // words missing from the lexicon are unknown
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        Ok(match self.lexicon.get(message) {
+            Some(bounds) => {
+                Segmentation::new("dictionary", bounds_to_offsets(bounds, message.len()))
+            }
+            None => Segmentation::unknown("dictionary", message),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::write_temp;
+
+    fn write_lexicon(name: &str, content: &str) -> String {
+        write_temp(name, content).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_morpho_challenge() {
+        let lexicon = write_lexicon(
+            "morpho_challenge.tsv",
+            "abacus's\tabacus:abacus_N 's:+GEN\n\
+             abandoned\tabandon:abandon_V ed:+PAST, abandon:abandon_V ed:+PCP2\n\
+             abandonment\tabandon:abandon_V ment:ment_s\n\
+             abbreviated\tab:ab_p brevi:brevi_A at:at_s ed:+PAST, abbreviate:abbreviate_V d:+PAST",
+        );
+
+        let mut dictionary = Dictionary::new(DictionaryConfig::morpho_challenge());
+
+        let report = dictionary.load(&lexicon).unwrap();
+
+        assert_eq!(report.entries, 4);
+        assert_eq!(
+            dictionary.segment("abacus's").unwrap().offsets,
+            vec![(0, 6), (6, 8)]
+        );
+        assert_eq!(
+            dictionary.segment("abandoned").unwrap().offsets,
+            vec![(0, 7), (7, 9)]
+        );
+        assert_eq!(
+            dictionary.segment("abbreviated").unwrap().offsets,
+            vec![(0, 2), (2, 7), (7, 9), (9, 11)]
+        );
+        assert_eq!(
+            dictionary.segment("abbreviate").unwrap().offsets,
+            vec![(0, 10)]
+        );
+        assert!(!dictionary.segment("abbreviate").unwrap().known);
+
+        let mut dictionary = Dictionary::new(DictionaryConfig {
+            multi_analysis: MultiAnalysis::Intersection,
+            ..DictionaryConfig::morpho_challenge()
+        });
+
+        dictionary.load(&lexicon).unwrap();
+
+        assert_eq!(
+            dictionary.segment("abbreviated").unwrap().offsets,
+            vec![(0, 11)]
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let lexicon = write_lexicon(
+            "sigmorphon.tsv",
+            "unfolded\tun @@fold @@ed\t110\n\
+             happiness\thappy @@ness\t010\n\
+             afghánských\tafghánsk @@ých\t010",
+        );
+
+        let mut dictionary = Dictionary::new(DictionaryConfig::sigmorphon());
+
+        let report = dictionary.load(&lexicon).unwrap();
+
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(
+            dictionary.segment("unfolded").unwrap().offsets,
+            vec![(0, 2), (2, 6), (6, 8)]
+        );
+        assert_eq!(
+            dictionary.segment("afghánských").unwrap().offsets,
+            vec![(0, 9), (9, 13)]
+        );
+        assert_eq!(
+            dictionary.segment("happiness").unwrap().offsets,
+            vec![(0, 9)]
+        );
+
+        let mut dictionary = Dictionary::new(DictionaryConfig {
+            mode: LoadMode::Strict,
+            ..DictionaryConfig::sigmorphon()
+        });
+
+        assert!(dictionary.load(&lexicon).is_err());
+    }
+}
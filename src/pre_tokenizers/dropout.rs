@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::segmenter::Segmentation;
+
+// drops each boundary proposed by the segmenter with probability drop_p
+// i.e. boundaries are kept with probability 1 - drop_p
+// only applied while training, which is disabled by default and for deserialized configs
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct BoundaryDropout {
+    pub drop_p: f64,
+    pub seed: u64,
+    #[serde(skip)]
+    pub training: bool,
+}
+
+impl BoundaryDropout {
+    pub fn new(drop_p: f64, seed: u64) -> Self {
+        BoundaryDropout {
+            drop_p,
+            seed,
+            training: false,
+        }
+    }
+
+    // mixes the whole input sequence into the seed, computed once per sequence before splitting
+    pub fn sequence_seed(&self, sequence: &str) -> u64 {
+        fnv1a(&[&self.seed.to_le_bytes(), sequence.as_bytes()])
+    }
+
+    // samples only depend on the sequence seed, the word and its offset within the sequence
+    // and are thus reproducible regardless of the order words are processed in
+    pub fn apply(
+        &self,
+        sequence_seed: u64,
+        word: &str,
+        offset: usize,
+        segmentation: Segmentation,
+    ) -> Segmentation {
+        if !self.training || segmentation.offsets.len() < 2 {
+            return segmentation;
+        }
+
+        let word_seed = fnv1a(&[
+            &sequence_seed.to_le_bytes(),
+            &(offset as u64).to_le_bytes(),
+            word.as_bytes(),
+        ]);
+
+        let mut offsets = vec![segmentation.offsets[0]];
+        let mut scores = vec![];
+
+        for (i, (start, end)) in segmentation.offsets.iter().enumerate().skip(1) {
+            if unit(splitmix64(word_seed.wrapping_add(i as u64))) < self.drop_p {
+                offsets.last_mut().unwrap().1 = *end;
+            } else {
+                offsets.push((*start, *end));
+
+                if let Some(score) = segmentation.scores.get(i - 1) {
+                    scores.push(*score);
+                }
+            }
+        }
+
+        Segmentation {
+            offsets,
+            scores,
+            ..segmentation
+        }
+    }
+}
+
+// hashing and sampling are implemented here rather than using the rand or std hashers
+// as their algorithms may change between versions, changing the samples of a given seed
+
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
+
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e3779b97f4a7c15);
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    z ^ (z >> 31)
+}
+
+// uniform in [0, 1)
+fn unit(bits: u64) -> f64 {
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segmentation() -> Segmentation {
+        Segmentation::with_scores(
+            "test",
+            vec![(0, 2), (2, 4), (4, 6), (6, 8)],
+            vec![0.1, 0.2, 0.3],
+        )
+    }
+
+    fn dropout(p: f64, seed: u64) -> BoundaryDropout {
+        let mut dropout = BoundaryDropout::new(p, seed);
+
+        dropout.training = true;
+
+        dropout
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(
+            dropout(0.0, 42).apply(0, "abcdefgh", 0, segmentation()),
+            segmentation()
+        );
+
+        let dropped = dropout(1.0, 42).apply(0, "abcdefgh", 0, segmentation());
+
+        assert_eq!(dropped.offsets, vec![(0, 8)]);
+        assert!(dropped.scores.is_empty());
+
+        // inactive unless training is enabled
+        assert_eq!(
+            BoundaryDropout::new(1.0, 42).apply(0, "abcdefgh", 0, segmentation()),
+            segmentation()
+        );
+    }
+
+    #[test]
+    fn test_seed() {
+        let sample = |dropout: &BoundaryDropout, sequence: &str| {
+            let sequence_seed = dropout.sequence_seed(sequence);
+
+            (0..20)
+                .map(|offset| {
+                    dropout
+                        .apply(sequence_seed, "abcdefgh", offset, segmentation())
+                        .offsets
+                })
+                .collect::<Vec<Vec<(usize, usize)>>>()
+        };
+
+        let a = sample(&dropout(0.5, 42), "abcdefgh");
+
+        assert_eq!(a, sample(&dropout(0.5, 42), "abcdefgh"));
+        assert_ne!(a, sample(&dropout(0.5, 43), "abcdefgh"));
+
+        // the same word at the same offset of another sequence
+        assert_ne!(a, sample(&dropout(0.5, 42), "abcdefgh ijk"));
+
+        // processing order does not matter
+        let sequence_seed = dropout(0.5, 42).sequence_seed("abcdefgh");
+
+        let reversed = (0..20)
+            .rev()
+            .map(|offset| {
+                dropout(0.5, 42)
+                    .apply(sequence_seed, "abcdefgh", offset, segmentation())
+                    .offsets
+            })
+            .collect::<Vec<Vec<(usize, usize)>>>();
+
+        assert_eq!(a, reversed.into_iter().rev().collect::<Vec<_>>());
+
+        for offsets in a {
+            assert!(Segmentation::new("test", offsets)
+                .validate("abcdefgh")
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_stable() {
+        // samples of a given seed must not change between versions
+        let dropout = dropout(0.5, 42);
+
+        let sequence_seed = dropout.sequence_seed("abcdefgh");
+
+        let offsets = (0..4)
+            .map(|offset| {
+                dropout
+                    .apply(sequence_seed, "abcdefgh", offset, segmentation())
+                    .offsets
+            })
+            .collect::<Vec<Vec<(usize, usize)>>>();
+
+        assert_eq!(
+            offsets,
+            vec![
+                vec![(0, 8)],
+                vec![(0, 6), (6, 8)],
+                vec![(0, 2), (2, 6), (6, 8)],
+                vec![(0, 6), (6, 8)],
+            ]
+        );
+    }
+}
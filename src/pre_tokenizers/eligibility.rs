@@ -0,0 +1,186 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::pre_byte_level::cached_regex;
+
+// decides which pieces are passed on to the segmenter, ineligible pieces are kept whole
+// the default config considers all pieces eligible
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct Eligibility {
+    // only letters and combining marks
+    pub letters_only: bool,
+    // lengths are counted in characters
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    // Unicode script names (e.g. Latin, Cyrillic), any script if empty
+    pub scripts: Vec<String>,
+    pub never_split: BTreeSet<String>,
+}
+
+impl Eligibility {
+    // fails for unknown script names
+    pub fn validate(&self) -> tokenizers::Result<()> {
+        if let Some(pattern) = self.script_pattern() {
+            cached_regex(&pattern)?;
+        }
+
+        Ok(())
+    }
+
+    // matches characters of other scripts, combining marks belong to the inherited script
+    fn script_pattern(&self) -> Option<String> {
+        if self.scripts.is_empty() {
+            return None;
+        }
+
+        let scripts = self
+            .scripts
+            .iter()
+            .map(|script| format!(r"\p{{{}}}", script))
+            .collect::<String>();
+
+        Some(format!(r"[^\p{{Inherited}}{}]", scripts))
+    }
+
+    // fails for unknown script names
+    pub fn is_eligible(&self, word: &str) -> tokenizers::Result<bool> {
+        if self.never_split.contains(word) {
+            return Ok(false);
+        }
+
+        let length = word.chars().count();
+
+        if length < self.min_length || self.max_length.is_some_and(|max| length > max) {
+            return Ok(false);
+        }
+
+        if self.letters_only
+            && cached_regex(r"[^\p{L}\p{M}]")?
+                .find_iter(word)
+                .next()
+                .is_some()
+        {
+            return Ok(false);
+        }
+
+        if let Some(pattern) = self.script_pattern() {
+            if cached_regex(&pattern)?.find_iter(word).next().is_some() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+// number of pieces passed on to the segmenter and kept whole
+// shared between clones, not part of the serialized config
+#[derive(Default)]
+pub struct Counters {
+    segmented: AtomicUsize,
+    filtered: AtomicUsize,
+    recovered: AtomicUsize, // pieces kept whole after failed segmentation
+}
+
+impl Counters {
+    pub fn segmented(&self) -> usize {
+        self.segmented.load(Ordering::Relaxed)
+    }
+
+    pub fn filtered(&self) -> usize {
+        self.filtered.load(Ordering::Relaxed)
+    }
+
+    pub fn recovered(&self) -> usize {
+        self.recovered.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.segmented.store(0, Ordering::Relaxed);
+        self.filtered.store(0, Ordering::Relaxed);
+        self.recovered.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count(&self, segmented: bool) {
+        if segmented {
+            self.segmented.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.filtered.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn recover(&self) {
+        self.recovered.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Debug for Counters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Counters")
+            .field("segmented", &self.segmented())
+            .field("filtered", &self.filtered())
+            .field("recovered", &self.recovered())
+            .finish()
+    }
+}
+
+// counters are diagnostics and do not affect equality
+impl PartialEq for Counters {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_eligible() {
+        let eligibility = Eligibility::default();
+
+        assert!(eligibility.is_eligible("1999").unwrap());
+        assert!(eligibility.is_eligible("   ").unwrap());
+
+        let eligibility = Eligibility {
+            letters_only: true,
+            min_length: 3,
+            max_length: Some(12),
+            never_split: BTreeSet::from(["afghánský".to_string()]),
+            ..Eligibility::default()
+        };
+
+        assert!(eligibility.is_eligible("afghánských").unwrap());
+        // decomposed accent
+        assert!(eligibility.is_eligible("afgha\u{301}nských").unwrap());
+        assert!(!eligibility.is_eligible("afghánský").unwrap());
+        assert!(!eligibility.is_eligible("1999").unwrap());
+        assert!(!eligibility.is_eligible("   ").unwrap());
+        assert!(!eligibility.is_eligible("an").unwrap());
+        assert!(!eligibility.is_eligible("afghánskýchxx").unwrap());
+    }
+
+    #[test]
+    fn test_scripts() {
+        let eligibility = Eligibility {
+            scripts: vec!["Latin".to_string()],
+            ..Eligibility::default()
+        };
+
+        assert!(eligibility.is_eligible("afgha\u{301}nských").unwrap());
+        assert!(!eligibility.is_eligible("афганский").unwrap());
+        assert!(!eligibility.is_eligible("afghan1").unwrap());
+
+        let eligibility = Eligibility {
+            scripts: vec!["Klingon".to_string()],
+            ..Eligibility::default()
+        };
+
+        assert!(eligibility.validate().is_err());
+        assert!(eligibility.is_eligible("afghánských").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+use crate::utils::offsets::bounds_to_offsets;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Vote {
+    // boundaries proposed by any member
+    Union,
+    // boundaries proposed by all members
+    Intersection,
+    // boundaries proposed by members holding more than half of the total weight
+    Majority,
+    // boundaries proposed by at least k members
+    AtLeast(usize),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct EnsembleMember {
+    pub segmenter: SegmenterWrapper,
+    pub weight: f64,
+}
+
+// members that do not know a word abstain from voting
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Ensemble {
+    pub members: Vec<EnsembleMember>,
+    pub vote: Vote,
+}
+
+impl Ensemble {
+    pub fn new(members: Vec<EnsembleMember>, vote: Vote) -> Self {
+        Ensemble { members, vote }
+    }
+
+    // members with equal weights
+    pub fn unweighted(segmenters: Vec<SegmenterWrapper>, vote: Vote) -> Self {
+        Ensemble {
+            members: segmenters
+                .into_iter()
+                .map(|segmenter| EnsembleMember {
+                    segmenter,
+                    weight: 1.0,
+                })
+                .collect(),
+            vote,
+        }
+    }
+}
+
+impl Segmenter for Ensemble {
+    // boundaries are scored by the share of the total weight that voted for them
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        // byte bounds and weight of every voting member
+        let mut ballots: Vec<(Vec<usize>, f64)> = vec![];
+
+        for member in &self.members {
+            let segmentation = member.segmenter.segment(message)?;
+
+            if segmentation.known {
+                ballots.push((segmentation.bounds(), member.weight));
+            }
+        }
+
+        if ballots.is_empty() {
+            return Ok(Segmentation::unknown("ensemble", message));
+        }
+
+        let mut candidates = ballots
+            .iter()
+            .flat_map(|(bounds, _)| bounds.iter().cloned())
+            .collect::<Vec<usize>>();
+
+        candidates.sort();
+        candidates.dedup();
+
+        let total = ballots.iter().map(|(_, weight)| weight).sum::<f64>();
+
+        let mut bounds = vec![];
+        let mut scores = vec![];
+
+        for bound in candidates {
+            let votes = ballots
+                .iter()
+                .filter(|(bounds, _)| bounds.contains(&bound))
+                .collect::<Vec<&(Vec<usize>, f64)>>();
+
+            let weight = votes.iter().map(|(_, weight)| weight).sum::<f64>();
+
+            let elected = match self.vote {
+                Vote::Union => true,
+                Vote::Intersection => votes.len() == ballots.len(),
+                Vote::Majority => weight > total / 2.0,
+                Vote::AtLeast(k) => votes.len() >= k,
+            };
+
+            if elected {
+                bounds.push(bound);
+                scores.push(if total > 0.0 { weight / total } else { 0.0 });
+            }
+        }
+
+        Ok(Segmentation::with_scores(
+            "ensemble",
+            bounds_to_offsets(&bounds, message.len()),
+            scores,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::{tree_split, write_temp};
+    use crate::pre_tokenizers::tree_split::Direction;
+
+    // prefix alignment yields nedobrý|mi, suffix alignment ne|dobrými
+    fn ces(direction: Direction) -> SegmenterWrapper {
+        let path = write_temp(
+            "ensemble.tsv",
+            "dobrý\tdobrými\tADJ;INS;PL\ndobrý\tnedobrý\tADJ;NEG\ndobrý\tnedobrými\tADJ;NEG;INS;PL",
+        );
+
+        SegmenterWrapper::TreeSplit(tree_split("ces", path, &["ces"], direction))
+    }
+
+    fn ensemble(weights: &[f64], vote: Vote) -> Ensemble {
+        let directions = [Direction::Prefix, Direction::Suffix, Direction::Both];
+
+        Ensemble::new(
+            directions
+                .iter()
+                .zip(weights)
+                .map(|(direction, weight)| EnsembleMember {
+                    segmenter: ces(*direction),
+                    weight: *weight,
+                })
+                .collect(),
+            vote,
+        )
+    }
+
+    #[test]
+    fn test_vote() {
+        let weights = [1.0, 1.0, 1.0];
+
+        assert_eq!(
+            ensemble(&weights, Vote::Union)
+                .segment("nedobrými")
+                .unwrap()
+                .offsets,
+            vec![(0, 2), (2, 8), (8, 10)]
+        );
+        assert_eq!(
+            ensemble(&weights, Vote::Intersection)
+                .segment("nedobrými")
+                .unwrap()
+                .offsets,
+            vec![(0, 10)]
+        );
+        assert_eq!(
+            ensemble(&weights, Vote::Majority)
+                .segment("nedobrými")
+                .unwrap()
+                .offsets,
+            vec![(0, 2), (2, 8), (8, 10)]
+        );
+        assert_eq!(
+            ensemble(&weights, Vote::AtLeast(3))
+                .segment("nedobrými")
+                .unwrap()
+                .offsets,
+            vec![(0, 10)]
+        );
+        assert_eq!(
+            ensemble(&[3.0, 1.0, 1.0], Vote::Majority)
+                .segment("nedobrými")
+                .unwrap()
+                .offsets,
+            vec![(0, 8), (8, 10)]
+        );
+    }
+
+    #[test]
+    fn test_scores() {
+        let segmentation = ensemble(&[2.0, 1.0, 1.0], Vote::Union)
+            .segment("nedobrými")
+            .unwrap();
+
+        assert_eq!(segmentation.offsets, vec![(0, 2), (2, 8), (8, 10)]);
+        assert_eq!(segmentation.scores, vec![0.5, 0.75]);
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert!(
+            !ensemble(&[1.0, 1.0, 1.0], Vote::Union)
+                .segment("foo")
+                .unwrap()
+                .known
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_string(&ensemble(&[3.0, 1.0, 1.0], Vote::AtLeast(2))).unwrap();
+
+        assert!(json.contains("\"weight\":3.0"));
+        assert!(json.contains("\"vote\":{\"AtLeast\":2}"));
+    }
+}
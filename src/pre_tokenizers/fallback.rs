@@ -0,0 +1,122 @@
+use std::io::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FallbackTrigger {
+    // words unknown to the primary segmenter
+    Unknown,
+    // unknown words and known words left unsplit by the primary segmenter
+    #[default]
+    Unsplit,
+}
+
+// e.g. TreeSplit for dictionary words and Morfessor for everything else
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Fallback {
+    pub primary: Box<SegmenterWrapper>,
+    pub secondary: Box<SegmenterWrapper>,
+    pub trigger: FallbackTrigger,
+}
+
+impl Fallback {
+    pub fn new(
+        primary: SegmenterWrapper,
+        secondary: SegmenterWrapper,
+        trigger: FallbackTrigger,
+    ) -> Self {
+        Fallback {
+            primary: Box::new(primary),
+            secondary: Box::new(secondary),
+            trigger,
+        }
+    }
+}
+
+impl Segmenter for Fallback {
+    // unknown only if unknown to both segmenters
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        let primary = self.primary.segment(message)?;
+
+        match self.trigger {
+            FallbackTrigger::Unknown if primary.known => return Ok(primary),
+            FallbackTrigger::Unsplit if primary.known && primary.offsets.len() > 1 => {
+                return Ok(primary)
+            }
+            _ => {}
+        }
+
+        let secondary = self.secondary.segment(message)?;
+
+        if secondary.known || !primary.known {
+            return Ok(secondary);
+        }
+
+        Ok(primary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::tree_split;
+    use crate::pre_tokenizers::tree_split::Direction;
+
+    fn ces(languages: &[&str], direction: Direction) -> SegmenterWrapper {
+        SegmenterWrapper::TreeSplit(tree_split("ces", "ces_afghansky.tsv", languages, direction))
+    }
+
+    #[test]
+    fn test_unknown() {
+        let fallback = Fallback::new(
+            ces(&["deu"], Direction::Prefix),
+            ces(&["ces"], Direction::Prefix),
+            FallbackTrigger::Unknown,
+        );
+
+        let segmentation = fallback.segment("afghánských").unwrap();
+
+        assert_eq!(segmentation.offsets, vec![(0, 11), (11, 13)]);
+        assert_eq!(segmentation.source, "tree_split");
+
+        let segmentation = fallback.segment("foo").unwrap();
+
+        assert_eq!(segmentation.offsets, vec![(0, 3)]);
+        assert!(!segmentation.known);
+    }
+
+    #[test]
+    fn test_unsplit() {
+        // afghánských is known but left unsplit by suffix alignment
+        let primary = ces(&["ces"], Direction::Suffix);
+
+        let segmentation = primary.segment("afghánských").unwrap();
+
+        assert_eq!(segmentation.offsets, vec![(0, 13)]);
+        assert!(segmentation.known);
+
+        let fallback = Fallback::new(
+            primary.clone(),
+            ces(&["ces"], Direction::Prefix),
+            FallbackTrigger::Unknown,
+        );
+
+        assert_eq!(
+            fallback.segment("afghánských").unwrap().offsets,
+            vec![(0, 13)]
+        );
+
+        let fallback = Fallback::new(
+            primary,
+            ces(&["ces"], Direction::Prefix),
+            FallbackTrigger::Unsplit,
+        );
+
+        assert_eq!(
+            fallback.segment("afghánských").unwrap().offsets,
+            vec![(0, 11), (11, 13)]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::pre_byte_level::{prefix_length, SplitPattern};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyThreshold {
+    // the given number of most frequent words
+    Rank(usize),
+    // words occurring at least the given number of times
+    Count(u64),
+}
+
+// word counts of a training corpus, words are split as seen by the segmenter
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct WordFrequencies {
+    pub counts: HashMap<String, u64>,
+}
+
+impl WordFrequencies {
+    // lines are split using the given pattern, mirroring PreByteLevel without prefix spaces
+    pub fn count_files(files: &[String], pattern: &SplitPattern) -> Result<Self, Error> {
+        let regex = pattern
+            .regex()
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err.to_string()))?;
+
+        let mut frequencies = WordFrequencies::default();
+
+        for file in files {
+            let reader = BufReader::new(File::open(file)?);
+
+            for line in reader.lines() {
+                let line = line?;
+
+                for (start, end) in regex.find_iter(&line) {
+                    let piece = &line[start..end];
+
+                    frequencies.add(&piece[prefix_length(piece, pattern)..], 1);
+                }
+            }
+        }
+
+        Ok(frequencies)
+    }
+
+    pub fn add(&mut self, word: &str, count: u64) {
+        if word.is_empty() {
+            return;
+        }
+
+        *self.counts.entry(word.to_string()).or_insert(0) += count;
+    }
+
+    // most frequent first, ties are ordered alphabetically
+    pub fn ranked(&self) -> Vec<(&str, u64)> {
+        let mut ranked = self
+            .counts
+            .iter()
+            .map(|(word, count)| (word.as_str(), *count))
+            .collect::<Vec<(&str, u64)>>();
+
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        ranked
+    }
+
+    pub fn frequent(&self, threshold: FrequencyThreshold) -> BTreeSet<String> {
+        let ranked = self.ranked();
+
+        let frequent = match threshold {
+            FrequencyThreshold::Rank(rank) => &ranked[..rank.min(ranked.len())],
+            FrequencyThreshold::Count(count) => {
+                &ranked[..ranked.partition_point(|(_, c)| *c >= count)]
+            }
+        };
+
+        frequent.iter().map(|(word, _)| word.to_string()).collect()
+    }
+
+    // one word<TAB>count entry per line, most frequent first
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        for (word, count) in self.ranked() {
+            writeln!(writer, "{}\t{}", word, count)?;
+        }
+
+        writer.flush()
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut frequencies = WordFrequencies::default();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            let invalid = || {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: line {}: expected word<TAB>count", path, i + 1),
+                )
+            };
+
+            let (word, count) = line.rsplit_once('\t').ok_or_else(invalid)?;
+
+            frequencies.add(word, count.parse().map_err(|_| invalid())?);
+        }
+
+        Ok(frequencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::write_temp;
+
+    #[test]
+    fn test_count_files() {
+        let path = write_temp("frequency.txt", "the cat and the dog\nthe dogs, and cats");
+
+        let frequencies = WordFrequencies::count_files(
+            &[path.to_str().unwrap().to_string()],
+            &SplitPattern::Gpt2,
+        )
+        .unwrap();
+
+        assert_eq!(frequencies.counts["the"], 3);
+        assert_eq!(frequencies.counts["and"], 2);
+        assert_eq!(frequencies.counts[","], 1);
+        assert_eq!(
+            frequencies.ranked()[..3],
+            [("the", 3), ("and", 2), (",", 1)]
+        );
+        assert_eq!(
+            frequencies.frequent(FrequencyThreshold::Rank(2)),
+            BTreeSet::from(["the".to_string(), "and".to_string()])
+        );
+        assert_eq!(
+            frequencies.frequent(FrequencyThreshold::Count(3)),
+            BTreeSet::from(["the".to_string()])
+        );
+        assert_eq!(frequencies.frequent(FrequencyThreshold::Rank(100)).len(), 7);
+
+        let path = write_temp("frequency.tsv", "");
+
+        frequencies.save(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(
+            WordFrequencies::load(path.to_str().unwrap()).unwrap(),
+            frequencies
+        );
+    }
+}
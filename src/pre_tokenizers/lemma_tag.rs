@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+use tokenizers::utils::macro_rules_attribute;
+use tokenizers::{impl_serde_type, NormalizedString, PreTokenizedString, PreTokenizer};
+
+use crate::unimorph::registry::UnimorphRegistry;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AmbiguityPolicy {
+    // lexicographically first (lemma, features) analysis
+    #[default]
+    First,
+    // lemma with the tags shared by all analyses, forms with multiple lemmas are kept
+    Common,
+    // forms with more than one analysis are kept
+    Keep,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct LemmaTag {
+    pub languages: Vec<String>,
+    pub policy: AmbiguityPolicy,
+    #[serde(skip_deserializing, skip_serializing)]
+    pub registry: UnimorphRegistry,
+}
+
+impl LemmaTag {
+    pub fn new(
+        registry: UnimorphRegistry,
+        languages: Vec<String>,
+        policy: AmbiguityPolicy,
+    ) -> Self {
+        Self {
+            languages,
+            policy,
+            registry,
+        }
+    }
+
+    // afghánské -> afghánský <ADJ> <GEN> <FEM> <SG>
+    fn factorize(&self, form: &str) -> Option<(String, Vec<String>)> {
+        let unimorph = self.registry.lookup(&self.languages, form)?;
+
+        let mut analyses: Vec<(String, String)> = vec![];
+
+        for lemma in unimorph.analyze(form) {
+            for features in unimorph.features(&lemma, form) {
+                analyses.push((lemma.clone(), features));
+            }
+        }
+
+        analyses.sort();
+
+        let (lemma, features) = match self.policy {
+            AmbiguityPolicy::First => analyses.first()?.clone(),
+            AmbiguityPolicy::Keep => {
+                if analyses.len() != 1 {
+                    return None;
+                }
+
+                analyses[0].clone()
+            }
+            AmbiguityPolicy::Common => {
+                let (lemma, first) = analyses.first()?;
+
+                if analyses.iter().any(|(other, _)| other != lemma) {
+                    return None;
+                }
+
+                let common = first
+                    .split(';')
+                    .filter(|tag| {
+                        analyses
+                            .iter()
+                            .all(|(_, features)| features.split(';').any(|other| other == *tag))
+                    })
+                    .collect::<Vec<&str>>()
+                    .join(";");
+
+                (lemma.clone(), common)
+            }
+        };
+
+        let tags = features
+            .split(';')
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| format!("<{}>", tag))
+            .collect();
+
+        Some((lemma, tags))
+    }
+}
+
+// replaces the content of a split while aligning it to the complete span of the original split
+fn rewrite(normalized: &NormalizedString, content: &str) -> NormalizedString {
+    let length = normalized.get().chars().count() as isize;
+
+    let mut rewritten = normalized.clone();
+
+    let dest = content
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match i {
+            0 if content.chars().count() == 1 || length < 2 => (c, 1 - length.max(1)),
+            0 => (c, 2 - length),
+            1 if length > 1 => (c, 0),
+            _ => (c, 1),
+        })
+        .collect::<Vec<(char, isize)>>();
+
+    rewritten.transform(dest, 0);
+
+    rewritten
+}
+
+impl PreTokenizer for LemmaTag {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
+        pretokenized.split(|_, normalized| {
+            let form = normalized.get();
+
+            let prefix_length = if form.starts_with(' ') { 1 } else { 0 };
+
+            let (lemma, tags) = match self.factorize(&form[prefix_length..]) {
+                Some(factors) => factors,
+                None => return Ok(vec![normalized]),
+            };
+
+            let mut result = vec![rewrite(
+                &normalized,
+                &(form[..prefix_length].to_owned() + &lemma),
+            )];
+
+            for tag in tags {
+                result.push(rewrite(&normalized, &tag));
+            }
+
+            Ok(result)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::offset_splits;
+
+    fn lemma_tag(policy: AmbiguityPolicy) -> LemmaTag {
+        let mut registry = UnimorphRegistry::new("");
+
+        registry.load_from("ces", "ces_afghansky.tsv").unwrap();
+
+        LemmaTag::new(registry, vec!["ces".to_string()], policy)
+    }
+
+    // words are split off with their leading space first, as by a preceding pre-tokenizer
+    fn splits(pre_tokenizer: &LemmaTag, text: &str) -> Vec<(String, (usize, usize))> {
+        let mut pretokenized = PreTokenizedString::from(text);
+
+        pretokenized
+            .split(|_, normalized| {
+                normalized.split(' ', tokenizers::SplitDelimiterBehavior::MergedWithNext)
+            })
+            .unwrap();
+
+        pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+
+        offset_splits(&pretokenized)
+    }
+
+    #[test]
+    fn test_first() {
+        let pre_tokenizer = lemma_tag(AmbiguityPolicy::First);
+
+        assert_eq!(
+            splits(&pre_tokenizer, "foo afghánské"),
+            vec![
+                ("foo".to_string(), (0, 3)),
+                (" afghánský".to_string(), (3, 15)),
+                ("<ADJ>".to_string(), (3, 15)),
+                ("<ACC>".to_string(), (3, 15)),
+                ("<FEM>".to_string(), (3, 15)),
+                ("<PL>".to_string(), (3, 15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_common() {
+        let pre_tokenizer = lemma_tag(AmbiguityPolicy::Common);
+
+        assert_eq!(
+            splits(&pre_tokenizer, "afghánského"),
+            vec![
+                ("afghánský".to_string(), (0, 13)),
+                ("<ADJ>".to_string(), (0, 13)),
+                ("<SG>".to_string(), (0, 13)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep() {
+        let pre_tokenizer = lemma_tag(AmbiguityPolicy::Keep);
+
+        assert_eq!(
+            splits(&pre_tokenizer, "afghánského afghánskou"),
+            vec![
+                ("afghánského".to_string(), (0, 13)),
+                (" afghánský".to_string(), (13, 25)),
+                ("<ADJ>".to_string(), (13, 25)),
+                ("<ACC>".to_string(), (13, 25)),
+                ("<FEM>".to_string(), (13, 25)),
+                ("<SG>".to_string(), (13, 25)),
+            ]
+        );
+    }
+}
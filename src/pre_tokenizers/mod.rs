@@ -1,40 +1,114 @@
+pub mod affix_strip;
+pub mod boundary_table;
+pub mod cascade;
+pub mod custom;
+pub mod dictionary;
+pub mod dropout;
+pub mod eligibility;
+pub mod ensemble;
 pub mod external;
+pub mod fallback;
+pub mod frequency;
+pub mod lemma_tag;
 pub mod morfessor;
 pub mod pre_byte_level;
+pub mod pre_metaspace;
+pub mod pre_word_piece;
+pub mod protected;
 pub mod segmenter;
 pub mod sequence;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod trace;
 pub mod tree_split;
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 use tokenizers::pre_tokenizers::byte_level::ByteLevel;
 use tokenizers::pre_tokenizers::PreTokenizerWrapper as TokenizersPreTokenizerWrapper;
 use tokenizers::{PreTokenizedString, PreTokenizer};
 
 use crate::pre_tokenizers::external::External;
+use crate::pre_tokenizers::lemma_tag::LemmaTag;
 use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
+use crate::pre_tokenizers::pre_metaspace::PreMetaspace;
+use crate::pre_tokenizers::pre_word_piece::PreWordPiece;
 use crate::pre_tokenizers::sequence::Sequence;
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum PreTokenizerWrapper {
     External(External),
     PreByteLevel(PreByteLevel),
+    PreMetaspace(PreMetaspace),
+    PreWordPiece(PreWordPiece),
+    LemmaTag(LemmaTag),
     Sequence(Sequence),
     TokenizersPreTokenizerWrapper(TokenizersPreTokenizerWrapper),
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+enum UntaggedPreTokenizer {
+    External(External),
+    Sequence(Sequence),
+    TokenizersPreTokenizerWrapper(TokenizersPreTokenizerWrapper),
+}
+
+// pre-tokenizers of this crate are deserialized by their type so that errors (e.g. unregistered
+// custom segmenters) are not hidden behind the untagged variants
+impl<'de> Deserialize<'de> for PreTokenizerWrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        let result = match value.get("type").and_then(Value::as_str) {
+            Some("PreByteLevel") => PreByteLevel::deserialize(value).map(Self::PreByteLevel),
+            Some("PreMetaspace") => PreMetaspace::deserialize(value).map(Self::PreMetaspace),
+            Some("PreWordPiece") => PreWordPiece::deserialize(value).map(Self::PreWordPiece),
+            Some("LemmaTag") => LemmaTag::deserialize(value).map(Self::LemmaTag),
+            _ => UntaggedPreTokenizer::deserialize(value).map(|untagged| match untagged {
+                UntaggedPreTokenizer::External(ext) => Self::External(ext),
+                UntaggedPreTokenizer::Sequence(seq) => Self::Sequence(seq),
+                UntaggedPreTokenizer::TokenizersPreTokenizerWrapper(ptw) => {
+                    Self::TokenizersPreTokenizerWrapper(ptw)
+                }
+            }),
+        };
+
+        result.map_err(D::Error::custom)
+    }
+}
+
 impl PreTokenizer for PreTokenizerWrapper {
     fn pre_tokenize(&self, normalized: &mut PreTokenizedString) -> tokenizers::Result<()> {
         match self {
             Self::External(ext) => ext.pre_tokenize(normalized),
             Self::PreByteLevel(pbl) => pbl.pre_tokenize(normalized),
+            Self::PreMetaspace(pms) => pms.pre_tokenize(normalized),
+            Self::PreWordPiece(pwp) => pwp.pre_tokenize(normalized),
+            Self::LemmaTag(lt) => lt.pre_tokenize(normalized),
             Self::Sequence(seq) => seq.pre_tokenize(normalized),
             Self::TokenizersPreTokenizerWrapper(ptw) => ptw.pre_tokenize(normalized),
         }
     }
 }
 
+impl PreTokenizerWrapper {
+    // enables or disables boundary dropout of nested PreByteLevel pre-tokenizers
+    // to be enabled before training and disabled afterwards
+    pub fn set_training(&mut self, training: bool) {
+        match self {
+            Self::PreByteLevel(pbl) => pbl.set_training(training),
+            Self::Sequence(seq) => seq.set_training(training),
+            _ => {}
+        }
+    }
+}
+
 // PreTokenizerWrapper::from(External::default());
 // PreTokenizerWrapper::External(External::default());
 impl From<External> for PreTokenizerWrapper {
@@ -47,6 +121,21 @@ impl From<PreByteLevel> for PreTokenizerWrapper {
         PreTokenizerWrapper::PreByteLevel(from)
     }
 }
+impl From<PreMetaspace> for PreTokenizerWrapper {
+    fn from(from: PreMetaspace) -> Self {
+        PreTokenizerWrapper::PreMetaspace(from)
+    }
+}
+impl From<PreWordPiece> for PreTokenizerWrapper {
+    fn from(from: PreWordPiece) -> Self {
+        PreTokenizerWrapper::PreWordPiece(from)
+    }
+}
+impl From<LemmaTag> for PreTokenizerWrapper {
+    fn from(from: LemmaTag) -> Self {
+        PreTokenizerWrapper::LemmaTag(from)
+    }
+}
 
 // PreTokenizerWrapper::from(Sequence::default());
 // PreTokenizerWrapper::TreeSplit(Sequence::default());
@@ -2,9 +2,10 @@ use crate::morfessor::morfessor;
 use crate::morfessor::morfessor::morfessor::BaselineModel;
 use crate::morfessor::morfessor::viterbi_segment;
 use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
-use crate::pre_tokenizers::segmenter::{Segmenter, SegmenterWrapper};
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
 use crate::utils::offsets::{collect_scalar_offsets, scalar_to_byte_offsets};
 use serde::{Deserialize, Serialize};
+use std::io::Error;
 
 pub fn new_pre_tokenizer(
     add_prefix_space: bool,
@@ -56,16 +57,17 @@ impl Default for MorfessorConfig {
 }
 
 impl Segmenter for Morfessor {
-    fn segment(&self, message: &str) -> Vec<(usize, usize)> {
+    // rejected segmentations are treated as unknown words
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
         let (segments, score) = viterbi_segment(
             &self.morfessor,
             message,
             self.config.viterbi_smoothing,
             self.config.viterbi_max_len,
-        );
+        )?;
 
         if self.config.rejection_threshold > 0.0 && score > self.config.rejection_threshold {
-            return vec![(0, message.len())];
+            return Ok(Segmentation::unknown("morfessor", message));
         }
 
         if self.config.reject_single_char_ngrams > 0 {
@@ -76,7 +78,7 @@ impl Segmenter for Morfessor {
                     sequence_len += 1;
 
                     if sequence_len >= self.config.reject_single_char_ngrams {
-                        return vec![(0, message.len())];
+                        return Ok(Segmentation::unknown("morfessor", message));
                     }
                 }
 
@@ -84,6 +86,18 @@ impl Segmenter for Morfessor {
             }
         }
 
-        scalar_to_byte_offsets(message, collect_scalar_offsets(segments))
+        let offsets = scalar_to_byte_offsets(message, collect_scalar_offsets(segments))?;
+
+        // the Viterbi cost is known for the whole word only, all boundaries share its score
+        // scores only reflect the cost relative to the rejection threshold, 1.0 if disabled
+        let score = if self.config.rejection_threshold > 0.0 {
+            (1.0 - score / self.config.rejection_threshold).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let scores = vec![score; offsets.len().saturating_sub(1)];
+
+        Ok(Segmentation::with_scores("morfessor", offsets, scores))
     }
 }
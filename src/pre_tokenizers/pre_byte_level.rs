@@ -1,19 +1,106 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, RwLock};
+
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
 use tokenizers::normalizer::Range;
 use tokenizers::utils::{macro_rules_attribute, SysRegex};
 use tokenizers::{
-    impl_serde_type, NormalizedString, PreTokenizedString, PreTokenizer, SplitDelimiterBehavior,
+    impl_serde_type, NormalizedString, OffsetReferential, OffsetType, PreTokenizedString,
+    PreTokenizer, SplitDelimiterBehavior,
 };
 
-use crate::pre_tokenizers::segmenter::{Segmenter, SegmenterWrapper};
+use crate::pre_tokenizers::dropout::BoundaryDropout;
+use crate::pre_tokenizers::eligibility::{Counters, Eligibility};
+use crate::pre_tokenizers::frequency::{FrequencyThreshold, WordFrequencies};
+use crate::pre_tokenizers::protected::ProtectedTerms;
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+use crate::pre_tokenizers::trace::{Trace, TraceStage};
+
+const GPT2: &str = r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+";
+
+// also used by Llama 3
+const CL100K: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+
+const O200K: &str = r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+(?!\S)|\s+";
 
 lazy_static! {
-    static ref RE: SysRegex = SysRegex::new(
-        r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+"
-    )
-    .unwrap();
+    static ref RE_GPT2: Arc<SysRegex> = Arc::new(SysRegex::new(GPT2).unwrap());
+    static ref RE_CL100K: Arc<SysRegex> = Arc::new(SysRegex::new(CL100K).unwrap());
+    static ref RE_O200K: Arc<SysRegex> = Arc::new(SysRegex::new(O200K).unwrap());
+    // compiled custom patterns
+    static ref RE_CUSTOM: RwLock<HashMap<String, Arc<SysRegex>>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub enum SplitPattern {
+    #[default]
+    Gpt2,
+    Cl100k,
+    O200k,
+    Llama3,
+    Custom(String),
+}
+
+impl SplitPattern {
+    pub fn pattern(&self) -> &str {
+        match self {
+            SplitPattern::Gpt2 => GPT2,
+            SplitPattern::Cl100k | SplitPattern::Llama3 => CL100K,
+            SplitPattern::O200k => O200K,
+            SplitPattern::Custom(pattern) => pattern,
+        }
+    }
+
+    // presets matching a single non-alphanumeric character in front of letters, e.g. "(test"
+    // GPT-2 keeps punctuation separate, contractions like "'s" are left as they are
+    pub fn attaches_punctuation(&self) -> bool {
+        matches!(
+            self,
+            SplitPattern::Cl100k | SplitPattern::O200k | SplitPattern::Llama3
+        )
+    }
+
+    pub fn regex(&self) -> tokenizers::Result<Arc<SysRegex>> {
+        let pattern = match self {
+            SplitPattern::Gpt2 => return Ok(RE_GPT2.clone()),
+            SplitPattern::Cl100k | SplitPattern::Llama3 => return Ok(RE_CL100K.clone()),
+            SplitPattern::O200k => return Ok(RE_O200K.clone()),
+            SplitPattern::Custom(pattern) => pattern,
+        };
+
+        cached_regex(pattern)
+    }
+}
+
+// compiles patterns once, shared by all pre-tokenizers
+pub(crate) fn cached_regex(pattern: &str) -> tokenizers::Result<Arc<SysRegex>> {
+    if let Some(regex) = RE_CUSTOM.read().unwrap().get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Arc::new(SysRegex::new(pattern)?);
+
+    RE_CUSTOM
+        .write()
+        .unwrap()
+        .insert(pattern.to_string(), regex.clone());
+
+    Ok(regex)
+}
+
+// handling of pieces that cannot be segmented, e.g. invalid offsets returned by custom segmenters
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RecoveryPolicy {
+    // pre-tokenization fails
+    #[default]
+    Fail,
+    // the piece is kept whole
+    KeepWhole,
+    // the piece is kept whole and a warning is logged
+    Warn,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -23,60 +110,612 @@ pub struct PreByteLevel {
     add_prefix_space: bool,
     use_regex: bool,
     segmenter: SegmenterWrapper,
+    #[serde(default)]
+    pattern: SplitPattern,
+    #[serde(default)]
+    eligibility: Eligibility,
+    #[serde(default)]
+    protected: ProtectedTerms,
+    #[serde(default)]
+    frequent: BTreeSet<String>, // kept whole, see pre_tokenizers::frequency
+    #[serde(default)]
+    dropout: Option<BoundaryDropout>,
+    #[serde(default)]
+    recovery: RecoveryPolicy,
+    #[serde(skip)]
+    counters: Arc<Counters>,
 }
 
 impl PreByteLevel {
-    pub fn new(add_prefix_space: bool, use_regex: bool, segmenter: SegmenterWrapper) -> Self {
+    // accepts custom segmenters, see pre_tokenizers::custom
+    pub fn new<S: Into<SegmenterWrapper>>(
+        add_prefix_space: bool,
+        use_regex: bool,
+        segmenter: S,
+    ) -> Self {
         Self {
             add_prefix_space,
             use_regex,
-            segmenter,
+            segmenter: segmenter.into(),
+            pattern: SplitPattern::Gpt2,
+            eligibility: Eligibility::default(),
+            protected: ProtectedTerms::default(),
+            frequent: BTreeSet::new(),
+            dropout: None,
+            recovery: RecoveryPolicy::Fail,
+            counters: Arc::new(Counters::default()),
         }
     }
+
+    // fails for invalid custom patterns
+    pub fn with_pattern(mut self, pattern: SplitPattern) -> tokenizers::Result<Self> {
+        pattern.regex()?;
+
+        self.pattern = pattern;
+
+        Ok(self)
+    }
+
+    // fails for unknown script names
+    pub fn with_eligibility(mut self, eligibility: Eligibility) -> tokenizers::Result<Self> {
+        eligibility.validate()?;
+
+        self.eligibility = eligibility;
+
+        Ok(self)
+    }
+
+    pub fn with_protected(mut self, protected: ProtectedTerms) -> Self {
+        self.protected = protected;
+
+        self
+    }
+
+    // words at or above the threshold are not segmented
+    pub fn with_frequencies(
+        mut self,
+        frequencies: &WordFrequencies,
+        threshold: FrequencyThreshold,
+    ) -> Self {
+        self.frequent = frequencies.frequent(threshold);
+
+        self
+    }
+
+    // boundaries are dropped with probability drop_p once training is enabled, see set_training
+    pub fn with_dropout(mut self, drop_p: f64, seed: u64) -> Self {
+        self.dropout = Some(BoundaryDropout::new(drop_p, seed));
+
+        self
+    }
+
+    // enables or disables boundary dropout, a no-op without dropout
+    pub fn set_training(&mut self, training: bool) {
+        if let Some(dropout) = &mut self.dropout {
+            dropout.training = training;
+        }
+    }
+
+    pub fn with_recovery(mut self, recovery: RecoveryPolicy) -> Self {
+        self.recovery = recovery;
+
+        self
+    }
+
+    pub fn counters(&self) -> &Counters {
+        &self.counters
+    }
 }
 
-impl PreTokenizer for PreByteLevel {
-    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
-        let re_ref: &SysRegex = &RE;
+// leading space or, for patterns attaching it to words, a single leading non-alphanumeric character
+// the prefix is kept on the first split but hidden from the segmenter
+pub(crate) fn prefix_length(form: &str, pattern: &SplitPattern) -> usize {
+    let mut chars = form.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(' '), _) => 1,
+        (Some(first), Some(second))
+            if pattern.attaches_punctuation()
+                && !first.is_alphanumeric()
+                && second.is_alphabetic() =>
+        {
+            first.len_utf8()
+        }
+        _ => 0,
+    }
+}
+
+impl PreByteLevel {
+    // splits after prefix space insertion, regex splitting and segmentation
+    pub fn trace(&self, message: &str) -> tokenizers::Result<Trace> {
+        let mut pretokenized = PreTokenizedString::from(message);
+
+        let mut stages = vec![];
+
+        self.trace_into(&mut pretokenized, &mut stages)?;
+
+        Ok(Trace {
+            input: message.to_string(),
+            stages,
+        })
+    }
+
+    pub(crate) fn trace_into(
+        &self,
+        pretokenized: &mut PreTokenizedString,
+        stages: &mut Vec<TraceStage>,
+    ) -> tokenizers::Result<()> {
+        self.run(pretokenized, Some(stages))
+    }
+
+    fn run(
+        &self,
+        pretokenized: &mut PreTokenizedString,
+        mut stages: Option<&mut Vec<TraceStage>>,
+    ) -> tokenizers::Result<()> {
+        // dropout samples depend on the whole input, hashed before splitting
+        let dropout = self.dropout.as_ref().map(|dropout| {
+            let sequence = pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(split, _, _)| split)
+                .collect::<String>();
+
+            (dropout, dropout.sequence_seed(&sequence))
+        });
 
         pretokenized.split(|_, mut normalized| {
             if self.add_prefix_space && !normalized.get().starts_with(' ') {
                 normalized.prepend(" ");
             }
 
-            if self.use_regex {
+            Ok(vec![normalized])
+        })?;
+
+        if let Some(stages) = stages.as_deref_mut() {
+            stages.push(TraceStage::new("prefix space", pretokenized, None));
+        }
+
+        // protected terms may span several pieces of the regex split
+        if let Some(protected) = self.protected.regex()? {
+            let re_ref: &SysRegex = &protected;
+
+            pretokenized.split(|_, normalized| {
+                normalized.split(re_ref, SplitDelimiterBehavior::Isolated)
+            })?;
+
+            if let Some(stages) = stages.as_deref_mut() {
+                stages.push(TraceStage::new("protected terms", pretokenized, None));
+            }
+        }
+
+        if self.use_regex {
+            let regex = self.pattern.regex()?;
+            let re_ref: &SysRegex = &regex;
+
+            pretokenized.split(|_, normalized| {
+                let form = normalized.get();
+
+                if !self.protected.is_empty()
+                    && self
+                        .protected
+                        .contains(form.strip_prefix(' ').unwrap_or(form))?
+                {
+                    return Ok(vec![normalized]);
+                }
+
                 normalized.split(re_ref, SplitDelimiterBehavior::Isolated)
-            } else {
-                Ok(vec![normalized])
+            })?;
+
+            if let Some(stages) = stages.as_deref_mut() {
+                stages.push(TraceStage::new("regex split", pretokenized, None));
             }
-        })?;
+        }
+
+        // provenance of each split, only collected while tracing
+        let mut sources: Vec<String> = vec![];
+
+        // traces are diagnostics and do not affect the counters
+        let counting = stages.is_none();
 
         pretokenized.split(|_, normalized| {
-            let form = normalized.get();
+            let prefix_length = prefix_length(normalized.get(), &self.pattern);
+
+            let word = &normalized.get()[prefix_length..];
 
-            let prefix_length = if form.starts_with(' ') { 1 } else { 0 };
+            // protected terms are checked first
+            let skipped = if self.protected.contains(word)? {
+                Some("protected")
+            } else if self.frequent.contains(word) {
+                Some("frequent")
+            } else if !self.eligibility.is_eligible(word)? {
+                Some("ineligible")
+            } else {
+                None
+            };
 
-            let splits = self.segmenter.segment(&form[prefix_length..]);
+            if let Some(skipped) = skipped {
+                if counting {
+                    self.counters.count(false);
+                }
 
-            let mut result: Vec<NormalizedString> = vec![];
+                if stages.is_some() {
+                    sources.push(skipped.to_string());
+                }
 
-            for (i, offsets) in splits.iter().enumerate() {
-                let left = if i == 0 {
-                    offsets.0
-                } else {
-                    offsets.0 + prefix_length
-                };
+                return Ok(vec![normalized]);
+            }
+
+            let segmented = split_segments(&self.segmenter, &normalized, prefix_length, dropout);
+
+            let (result, segmentation) = match (segmented, self.recovery) {
+                (Ok(segmented), _) => segmented,
+                (Err(err), RecoveryPolicy::Fail) => return Err(err),
+                (Err(err), recovery) => {
+                    if recovery == RecoveryPolicy::Warn {
+                        log::warn!("keeping '{}' whole: {}", normalized.get(), err);
+                    }
+
+                    if counting {
+                        self.counters.recover();
+                    }
+
+                    if stages.is_some() {
+                        sources.push("recovered".to_string());
+                    }
 
-                let right = offsets.1 + prefix_length;
+                    return Ok(vec![normalized]);
+                }
+            };
 
-                result.push(normalized.slice(Range::Normalized(left..right)).unwrap());
+            // recovered pieces are only counted as recovered
+            if counting {
+                self.counters.count(true);
             }
 
-            if result.is_empty() {
-                panic!("empty segmentation");
+            if stages.is_some() {
+                let source = if segmentation.known {
+                    segmentation.source
+                } else {
+                    format!("{} (unknown)", segmentation.source)
+                };
+
+                sources.extend(std::iter::repeat_n(source, result.len()));
             }
 
-            return Ok(result);
-        })
+            Ok(result)
+        })?;
+
+        if let Some(stages) = stages {
+            stages.push(TraceStage::new("segmentation", pretokenized, Some(sources)));
+        }
+
+        Ok(())
+    }
+}
+
+impl PreTokenizer for PreByteLevel {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
+        self.run(pretokenized, None)
+    }
+}
+
+// splits a piece along the segmentation of everything following its prefix
+// the applied segmentation is returned alongside the splits
+pub(crate) fn split_segments(
+    segmenter: &SegmenterWrapper,
+    normalized: &NormalizedString,
+    prefix_length: usize,
+    dropout: Option<(&BoundaryDropout, u64)>,
+) -> tokenizers::Result<(Vec<NormalizedString>, Segmentation)> {
+    let form = normalized.get();
+
+    let segmentation = segmenter.segment(&form[prefix_length..])?;
+
+    segmentation.validate(&form[prefix_length..])?;
+
+    let segmentation = match dropout {
+        Some((dropout, sequence_seed)) => dropout.apply(
+            sequence_seed,
+            &form[prefix_length..],
+            normalized.offsets_original().0,
+            segmentation,
+        ),
+        None => segmentation,
+    };
+
+    let mut result: Vec<NormalizedString> = vec![];
+
+    for (i, offsets) in segmentation.offsets.iter().enumerate() {
+        let left = if i == 0 {
+            offsets.0
+        } else {
+            offsets.0 + prefix_length
+        };
+
+        let right = offsets.1 + prefix_length;
+
+        let slice = normalized
+            .slice(Range::Normalized(left..right))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid slice {:?} of '{}'", left..right, form),
+                )
+            })?;
+
+        result.push(slice);
+    }
+
+    Ok((result, segmentation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::affix_strip::{AffixStrip, AffixStripConfig};
+    use crate::pre_tokenizers::test_utils::{affix_strip_un_ed, splits, Halves};
+
+    #[test]
+    fn test_pattern() {
+        let splits = |pattern: SplitPattern, message: &str| {
+            let segmenter = AffixStrip::new(AffixStripConfig {
+                suffixes: vec!["ing".to_string()],
+                ..AffixStripConfig::default()
+            });
+
+            let pre_tokenizer =
+                PreByteLevel::new(false, true, SegmenterWrapper::AffixStrip(segmenter))
+                    .with_pattern(pattern)
+                    .unwrap();
+
+            splits(&pre_tokenizer, message)
+        };
+
+        assert_eq!(
+            splits(SplitPattern::Gpt2, "WE'RE testing 12345"),
+            vec!["WE", "'", "RE", " test", "ing", " 12345"]
+        );
+        assert_eq!(
+            splits(SplitPattern::Cl100k, "WE'RE testing 12345"),
+            vec!["WE", "'RE", " test", "ing", " ", "123", "45"]
+        );
+        assert_eq!(
+            splits(SplitPattern::Llama3, "(testing"),
+            vec!["(test", "ing"]
+        );
+        assert_eq!(
+            splits(SplitPattern::Custom(r"\S+|\s+".to_string()), "testing 1"),
+            vec!["test", "ing", " ", "1"]
+        );
+    }
+
+    #[test]
+    fn test_contractions() {
+        // contractions reach the segmenter with their apostrophe
+        assert_eq!(prefix_length("'s", &SplitPattern::Gpt2), 0);
+        assert_eq!(prefix_length(" cats", &SplitPattern::Gpt2), 1);
+        assert_eq!(prefix_length("'s", &SplitPattern::Cl100k), 1);
+
+        let segmenter = AffixStrip::new(AffixStripConfig {
+            suffixes: vec!["s".to_string(), "t".to_string()],
+            min_stem_length: 2,
+            max_affixes: 1,
+            ..AffixStripConfig::default()
+        });
+
+        let pre_tokenizer = PreByteLevel::new(false, true, SegmenterWrapper::AffixStrip(segmenter));
+
+        assert_eq!(
+            splits(&pre_tokenizer, "it's cats don't"),
+            vec!["it", "'s", " cat", "s", " don", "'t"]
+        );
+    }
+
+    #[test]
+    fn test_eligibility() {
+        let segmenter = AffixStrip::new(AffixStripConfig {
+            suffixes: vec!["ing".to_string(), "99".to_string()],
+            ..AffixStripConfig::default()
+        });
+
+        let pre_tokenizer = PreByteLevel::new(false, true, SegmenterWrapper::AffixStrip(segmenter))
+            .with_eligibility(Eligibility {
+                letters_only: true,
+                never_split: BTreeSet::from(["Beijing".to_string()]),
+                ..Eligibility::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            splits(&pre_tokenizer, "testing 1999 in Beijing"),
+            vec!["test", "ing", " 1999", " in", " Beijing"]
+        );
+        assert_eq!(pre_tokenizer.counters().segmented(), 2);
+        assert_eq!(pre_tokenizer.counters().filtered(), 2);
+
+        pre_tokenizer.counters().reset();
+
+        assert_eq!(pre_tokenizer.counters().segmented(), 0);
+
+        // tracing does not count
+        pre_tokenizer.trace("testing 1999").unwrap();
+
+        assert_eq!(pre_tokenizer.counters().segmented(), 0);
+        assert_eq!(pre_tokenizer.counters().filtered(), 0);
+    }
+
+    #[test]
+    fn test_protected() {
+        let segmenter = AffixStrip::new(AffixStripConfig {
+            suffixes: vec!["ing".to_string()],
+            ..AffixStripConfig::default()
+        });
+
+        let mut protected = ProtectedTerms::new(true);
+
+        protected.insert("Beijing");
+        protected.insert("New York");
+        protected.insert("C++");
+        protected.add_pattern(r"[a-z]+_[a-z]+").unwrap();
+
+        let pre_tokenizer = PreByteLevel::new(false, true, SegmenterWrapper::AffixStrip(segmenter))
+            .with_protected(protected);
+
+        let splits = |message: &str| splits(&pre_tokenizer, message);
+
+        assert_eq!(splits("BEIJING"), vec!["BEIJING"]);
+        assert_eq!(
+            splits("is_testing in new york"),
+            vec!["is_testing", " in", " new york"]
+        );
+        assert_eq!(splits("IS_TESTING"), vec!["IS_TESTING"]);
+        assert_eq!(splits("C++ testing"), vec!["C++", " test", "ing"]);
+        assert_eq!(splits("testing"), vec!["test", "ing"]);
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.contains("\"terms\":[\"beijing\",\"c++\",\"new york\"]"));
+        assert_eq!(
+            serde_json::from_str::<PreByteLevel>(&json).unwrap(),
+            pre_tokenizer
+        );
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let segmenter = AffixStrip::new(AffixStripConfig {
+            suffixes: vec!["s".to_string(), "ing".to_string()],
+            ..AffixStripConfig::default()
+        });
+
+        let mut frequencies = WordFrequencies::default();
+
+        frequencies.add("things", 10);
+        frequencies.add("testing", 2);
+
+        let pre_tokenizer = PreByteLevel::new(false, true, SegmenterWrapper::AffixStrip(segmenter))
+            .with_frequencies(&frequencies, FrequencyThreshold::Rank(1));
+
+        assert_eq!(
+            splits(&pre_tokenizer, "things testing"),
+            vec!["things", " test", "ing"]
+        );
+        assert!(serde_json::to_string(&pre_tokenizer)
+            .unwrap()
+            .contains("\"frequent\":[\"things\"]"));
+    }
+
+    #[test]
+    fn test_dropout() {
+        let mut pre_tokenizer =
+            PreByteLevel::new(false, true, affix_strip_un_ed()).with_dropout(1.0, 42);
+
+        let splits = |pre_tokenizer: &PreByteLevel| splits(pre_tokenizer, "unfolded");
+
+        // inactive until training is enabled
+        assert_eq!(splits(&pre_tokenizer), vec!["un", "fold", "ed"]);
+
+        pre_tokenizer.set_training(true);
+
+        assert_eq!(splits(&pre_tokenizer), vec!["unfolded"]);
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.contains("\"dropout\":{\"drop_p\":1.0,\"seed\":42}"));
+
+        // loaded configs are used for inference
+        assert_eq!(
+            splits(&serde_json::from_str::<PreByteLevel>(&json).unwrap()),
+            vec!["un", "fold", "ed"]
+        );
+    }
+
+    #[test]
+    fn test_dropout_sequence() {
+        use crate::pre_tokenizers::sequence::Sequence;
+        use crate::pre_tokenizers::PreTokenizerWrapper;
+        use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+        use tokenizers::pre_tokenizers::PreTokenizerWrapper as TokenizersPreTokenizerWrapper;
+
+        let mut sequence = PreTokenizerWrapper::from(Sequence::new(vec![
+            PreByteLevel::new(false, true, affix_strip_un_ed())
+                .with_dropout(1.0, 42)
+                .into(),
+            PreTokenizerWrapper::from(TokenizersPreTokenizerWrapper::from(ByteLevel::new(
+                false, false, false,
+            ))),
+        ]));
+
+        let splits = |sequence: &PreTokenizerWrapper| splits(sequence, "unfolded");
+
+        // enabled for training, e.g. around Tokenizer::train_from_files, and disabled afterwards
+        sequence.set_training(true);
+
+        assert_eq!(splits(&sequence), vec!["unfolded"]);
+
+        sequence.set_training(false);
+
+        assert_eq!(splits(&sequence), vec!["un", "fold", "ed"]);
+    }
+
+    #[test]
+    fn test_recovery() {
+        // " ačas" is split within "č"
+        let mut pretokenized = PreTokenizedString::from("abcd ačas");
+
+        assert!(PreByteLevel::new(false, true, Halves)
+            .pre_tokenize(&mut pretokenized)
+            .is_err());
+
+        for recovery in [RecoveryPolicy::KeepWhole, RecoveryPolicy::Warn] {
+            let pre_tokenizer = PreByteLevel::new(false, true, Halves).with_recovery(recovery);
+
+            assert_eq!(
+                splits(&pre_tokenizer, "abcd ačas"),
+                vec!["ab", "cd", " ačas"]
+            );
+            assert_eq!(pre_tokenizer.counters().recovered(), 1);
+            assert_eq!(pre_tokenizer.counters().segmented(), 1);
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let pre_tokenizer = PreByteLevel::new(
+            true,
+            true,
+            SegmenterWrapper::AffixStrip(AffixStrip::new(AffixStripConfig::default())),
+        );
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.contains("\"pattern\":\"Gpt2\""));
+
+        // configs without a pattern default to GPT-2
+        let json = json.replace(",\"pattern\":\"Gpt2\"", "");
+
+        assert_eq!(
+            serde_json::from_str::<PreByteLevel>(&json).unwrap(),
+            pre_tokenizer
+        );
+
+        let pre_tokenizer = pre_tokenizer
+            .with_pattern(SplitPattern::Custom(r"\p{L}+".to_string()))
+            .unwrap();
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<PreByteLevel>(&json).unwrap(),
+            pre_tokenizer
+        );
+        assert!(PreByteLevel::new(
+            true,
+            true,
+            SegmenterWrapper::AffixStrip(AffixStrip::new(AffixStripConfig::default()))
+        )
+        .with_pattern(SplitPattern::Custom("(".to_string()))
+        .is_err());
     }
 }
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use tokenizers::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
+use tokenizers::utils::macro_rules_attribute;
+use tokenizers::{impl_serde_type, PreTokenizedString, PreTokenizer};
+
+use crate::pre_tokenizers::pre_byte_level::split_segments;
+use crate::pre_tokenizers::segmenter::SegmenterWrapper;
+
+// counterpart of PreByteLevel for SentencePiece-like tokenizers (Llama, T5, mT5)
+// words are segmented after metaspace handling, the replacement is kept on the first morph
+#[derive(Clone, Debug, PartialEq)]
+#[macro_rules_attribute(impl_serde_type!)]
+#[non_exhaustive]
+pub struct PreMetaspace {
+    replacement: char,
+    add_prefix_space: bool,
+    prepend_scheme: PrependScheme,
+    segmenter: SegmenterWrapper,
+}
+
+impl PreMetaspace {
+    pub fn new<S: Into<SegmenterWrapper>>(
+        replacement: char,
+        add_prefix_space: bool,
+        prepend_scheme: PrependScheme,
+        segmenter: S,
+    ) -> Self {
+        Self {
+            replacement,
+            add_prefix_space,
+            prepend_scheme,
+            segmenter: segmenter.into(),
+        }
+    }
+}
+
+impl PreTokenizer for PreMetaspace {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
+        Metaspace::new_with_prepend_scheme(
+            self.replacement,
+            self.add_prefix_space,
+            self.prepend_scheme,
+        )
+        .pre_tokenize(pretokenized)?;
+
+        pretokenized.split(|_, normalized| {
+            let prefix_length = if normalized.get().starts_with(self.replacement) {
+                self.replacement.len_utf8()
+            } else {
+                0
+            };
+
+            Ok(split_segments(&self.segmenter, &normalized, prefix_length, None)?.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::{
+        affix_strip_un_ed, offset_splits, splits_with_offsets,
+    };
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    fn pre_metaspace(prepend_scheme: PrependScheme) -> PreMetaspace {
+        PreMetaspace::new('▁', true, prepend_scheme, affix_strip_un_ed())
+    }
+
+    #[test]
+    fn test_pre_tokenize() {
+        assert_eq!(
+            splits_with_offsets(&pre_metaspace(PrependScheme::Always), "unfolded it"),
+            vec![
+                ("▁un".to_string(), (0, 2)),
+                ("fold".to_string(), (2, 6)),
+                ("ed".to_string(), (6, 8)),
+                ("▁it".to_string(), (8, 11)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prepend_scheme() {
+        let mut pretokenized = PreTokenizedString::from("unfolded");
+
+        pretokenized
+            .split(|_, normalized| Ok(vec![normalized.clone(), normalized]))
+            .unwrap();
+
+        pre_metaspace(PrependScheme::First)
+            .pre_tokenize(&mut pretokenized)
+            .unwrap();
+
+        let first = offset_splits(&pretokenized)
+            .into_iter()
+            .map(|(split, _)| split)
+            .collect::<Vec<String>>();
+
+        assert_eq!(first, vec!["▁un", "fold", "ed", "un", "fold", "ed"]);
+
+        assert_eq!(
+            splits_with_offsets(&pre_metaspace(PrependScheme::Never), "unfolded"),
+            vec![
+                ("un".to_string(), (0, 2)),
+                ("fold".to_string(), (2, 6)),
+                ("ed".to_string(), (6, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let pre_tokenizer = PreTokenizerWrapper::from(pre_metaspace(PrependScheme::First));
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.starts_with("{\"type\":\"PreMetaspace\""));
+        assert!(json.contains("\"prepend_scheme\":\"first\""));
+        assert_eq!(
+            serde_json::from_str::<PreTokenizerWrapper>(&json).unwrap(),
+            pre_tokenizer
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+use tokenizers::decoders::wordpiece::WordPiece;
+use tokenizers::pre_tokenizers::bert::BertPreTokenizer;
+use tokenizers::utils::macro_rules_attribute;
+use tokenizers::{impl_serde_type, PreTokenizedString, PreTokenizer};
+
+use crate::pre_tokenizers::pre_byte_level::split_segments;
+use crate::pre_tokenizers::segmenter::SegmenterWrapper;
+
+// counterpart of PreByteLevel for BERT-style tokenizers
+// non-initial morphs can be marked with the continuation prefix, WordPiece then treats them as continuations
+// prefixes made of punctuation (e.g. "##") are split off by BertPreTokenizer and never start a word,
+// other prefixes cannot be told apart from words starting with them
+#[derive(Clone, Debug, PartialEq)]
+#[macro_rules_attribute(impl_serde_type!)]
+#[non_exhaustive]
+pub struct PreWordPiece {
+    continuation_prefix: String,
+    mark_continuations: bool,
+    segmenter: SegmenterWrapper,
+}
+
+impl PreWordPiece {
+    pub fn new<S: Into<SegmenterWrapper>>(
+        continuation_prefix: &str,
+        mark_continuations: bool,
+        segmenter: S,
+    ) -> Self {
+        Self {
+            continuation_prefix: continuation_prefix.to_string(),
+            mark_continuations,
+            segmenter: segmenter.into(),
+        }
+    }
+
+    // marked morphs are glued to the preceding token like regular continuations
+    // cleanup is disabled since it would alter words containing apostrophes
+    pub fn decoder(&self) -> WordPiece {
+        WordPiece::new(self.continuation_prefix.clone(), false)
+    }
+}
+
+impl PreTokenizer for PreWordPiece {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> tokenizers::Result<()> {
+        BertPreTokenizer.pre_tokenize(pretokenized)?;
+
+        pretokenized.split(|_, normalized| {
+            let (mut result, _) = split_segments(&self.segmenter, &normalized, 0, None)?;
+
+            if self.mark_continuations {
+                for morph in result.iter_mut().skip(1) {
+                    morph.prepend(&self.continuation_prefix);
+                }
+            }
+
+            Ok(result)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::{affix_strip_un_ed, splits_with_offsets, write_temp};
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+    use std::collections::HashMap;
+    use tokenizers::models::wordpiece::{WordPiece as WordPieceModel, WordPieceTrainer};
+    use tokenizers::models::TrainerWrapper;
+    use tokenizers::{
+        AddedToken, DecoderWrapper, ModelWrapper, NormalizerWrapper, PostProcessorWrapper,
+        TokenizerBuilder,
+    };
+
+    fn pre_word_piece(mark_continuations: bool) -> PreWordPiece {
+        PreWordPiece::new("##", mark_continuations, affix_strip_un_ed())
+    }
+
+    #[test]
+    fn test_pre_tokenize() {
+        assert_eq!(
+            splits_with_offsets(&pre_word_piece(true), "unfolded, it"),
+            vec![
+                ("un".to_string(), (0, 2)),
+                ("##fold".to_string(), (2, 6)),
+                ("##ed".to_string(), (6, 8)),
+                (",".to_string(), (8, 9)),
+                ("it".to_string(), (10, 12)),
+            ]
+        );
+        assert_eq!(
+            splits_with_offsets(&pre_word_piece(false), "unfolded"),
+            vec![
+                ("un".to_string(), (0, 2)),
+                ("fold".to_string(), (2, 6)),
+                ("ed".to_string(), (6, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let vocab = ["[UNK]", "un", "##fold", "##ed", "##s", "it", ","]
+            .iter()
+            .enumerate()
+            .map(|(i, token)| (token.to_string(), i as u32))
+            .collect::<HashMap<String, u32>>();
+
+        let pre_word_piece = pre_word_piece(true);
+
+        let tokenizer = TokenizerBuilder::<
+            ModelWrapper,
+            NormalizerWrapper,
+            PreTokenizerWrapper,
+            PostProcessorWrapper,
+            DecoderWrapper,
+        >::default()
+        .with_model(ModelWrapper::from(
+            WordPieceModel::builder().vocab(vocab).build().unwrap(),
+        ))
+        .with_pre_tokenizer(Some(PreTokenizerWrapper::from(pre_word_piece.clone())))
+        .with_decoder(Some(DecoderWrapper::from(pre_word_piece.decoder())))
+        .build()
+        .unwrap();
+
+        let encoding = tokenizer.encode("unfolded, it", false).unwrap();
+
+        assert_eq!(encoding.get_tokens(), ["un", "##fold", "##ed", ",", "it"]);
+        assert_eq!(
+            tokenizer.decode(encoding.get_ids(), false).unwrap(),
+            "unfolded , it"
+        );
+    }
+
+    #[test]
+    fn test_train() {
+        let path = write_temp("pre_word_piece.txt", "unfolded unfolds\nfolded refolded\n");
+
+        let pre_word_piece = pre_word_piece(true);
+
+        let mut tokenizer = TokenizerBuilder::<
+            ModelWrapper,
+            NormalizerWrapper,
+            PreTokenizerWrapper,
+            PostProcessorWrapper,
+            DecoderWrapper,
+        >::default()
+        .with_model(ModelWrapper::from(WordPieceModel::default()))
+        .with_pre_tokenizer(Some(PreTokenizerWrapper::from(pre_word_piece.clone())))
+        .with_decoder(Some(DecoderWrapper::from(pre_word_piece.decoder())))
+        .build()
+        .unwrap();
+
+        let mut trainer = TrainerWrapper::from(
+            WordPieceTrainer::builder()
+                .show_progress(false)
+                .vocab_size(100)
+                .special_tokens(vec![AddedToken::from("[UNK]", true)])
+                .build(),
+        );
+
+        tokenizer
+            .train_from_files(&mut trainer, vec![path.to_str().unwrap().to_string()])
+            .unwrap();
+
+        let encoding = tokenizer.encode("unfolded refolds", false).unwrap();
+
+        assert_eq!(
+            encoding.get_tokens(),
+            ["un", "##fold", "##ed", "refold", "##s"]
+        );
+        assert_eq!(
+            tokenizer.decode(encoding.get_ids(), false).unwrap(),
+            "unfolded refolds"
+        );
+    }
+
+    #[test]
+    fn test_continuation_prefix() {
+        // the prefix is punctuation and isolated by BertPreTokenizer, words never start with it
+        assert_eq!(
+            splits_with_offsets(&pre_word_piece(true), "##ed"),
+            vec![
+                ("#".to_string(), (0, 1)),
+                ("#".to_string(), (1, 2)),
+                ("ed".to_string(), (2, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let pre_tokenizer = PreTokenizerWrapper::from(pre_word_piece(true));
+
+        let json = serde_json::to_string(&pre_tokenizer).unwrap();
+
+        assert!(json.starts_with("{\"type\":\"PreWordPiece\""));
+        assert_eq!(
+            serde_json::from_str::<PreTokenizerWrapper>(&json).unwrap(),
+            pre_tokenizer
+        );
+    }
+}
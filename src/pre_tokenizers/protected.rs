@@ -0,0 +1,220 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use tokenizers::utils::SysRegex;
+
+use crate::pre_tokenizers::pre_byte_level::cached_regex;
+
+// named entities, identifiers and domain terms that are never segmented
+// terms may span multiple words and punctuation (e.g. "New York", "C++"), occurrences are isolated
+// before regex splitting, see ProtectedTerms::regex
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct ProtectedTerms {
+    case_insensitive: bool,
+    terms: BTreeSet<String>, // lowercased if matched case-insensitively
+    patterns: Vec<String>,   // case-insensitive alongside the terms
+}
+
+impl ProtectedTerms {
+    pub fn new(case_insensitive: bool) -> Self {
+        ProtectedTerms {
+            case_insensitive,
+            ..ProtectedTerms::default()
+        }
+    }
+
+    pub fn insert(&mut self, term: &str) {
+        if self.case_insensitive {
+            self.terms.insert(term.to_lowercase());
+        } else {
+            self.terms.insert(term.to_string());
+        }
+    }
+
+    // one term per line, empty lines are skipped
+    pub fn load(&mut self, path: &str) -> Result<usize, Error> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut count = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let term = line.trim();
+
+            if term.is_empty() {
+                continue;
+            }
+
+            self.insert(term);
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    // fails for invalid patterns
+    pub fn add_pattern(&mut self, pattern: &str) -> tokenizers::Result<()> {
+        cached_regex(&self.anchor(pattern))?;
+
+        self.patterns.push(pattern.to_string());
+
+        Ok(())
+    }
+
+    fn anchor(&self, pattern: &str) -> String {
+        format!(r"{}\A(?:{})\z", self.flags(), pattern)
+    }
+
+    fn flags(&self) -> &str {
+        if self.case_insensitive {
+            "(?i)"
+        } else {
+            ""
+        }
+    }
+
+    // matches terms and patterns as whole words alongside a single leading space
+    // longer terms are preferred, None without terms and patterns
+    pub(crate) fn regex(&self) -> tokenizers::Result<Option<Arc<SysRegex>>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        let mut terms = self.terms.iter().collect::<Vec<&String>>();
+
+        terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+
+        let alternatives = terms
+            .into_iter()
+            .map(|term| escape(term))
+            .chain(
+                self.patterns
+                    .iter()
+                    .map(|pattern| format!("(?:{})", pattern)),
+            )
+            .collect::<Vec<String>>()
+            .join("|");
+
+        let pattern = format!(
+            r"{} ?(?<![\p{{L}}\p{{N}}])(?:{})(?![\p{{L}}\p{{N}}])",
+            self.flags(),
+            alternatives
+        );
+
+        Ok(Some(cached_regex(&pattern)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.patterns.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> tokenizers::Result<bool> {
+        let found = if self.case_insensitive {
+            self.terms.contains(&word.to_lowercase())
+        } else {
+            self.terms.contains(word)
+        };
+
+        if found {
+            return Ok(true);
+        }
+
+        for pattern in &self.patterns {
+            if cached_regex(&self.anchor(pattern))?
+                .find_iter(word)
+                .next()
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+// escapes regex metacharacters of a literal term
+fn escape(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+
+    for c in term.chars() {
+        if r"\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::test_utils::write_temp;
+
+    #[test]
+    fn test_contains() {
+        let path = write_temp("protected.txt", "Morfessor\n\nacetylsalicylic\n");
+
+        let mut protected = ProtectedTerms::new(false);
+
+        assert_eq!(protected.load(path.to_str().unwrap()).unwrap(), 2);
+        assert!(protected.contains("Morfessor").unwrap());
+        assert!(!protected.contains("morfessor").unwrap());
+
+        let mut protected = ProtectedTerms::new(true);
+
+        protected.load(path.to_str().unwrap()).unwrap();
+
+        assert!(protected.contains("MORFESSOR").unwrap());
+        assert!(protected.contains("Acetylsalicylic").unwrap());
+        assert!(!protected.contains("Morfessors").unwrap());
+    }
+
+    #[test]
+    fn test_patterns() {
+        let mut protected = ProtectedTerms::new(false);
+
+        protected.add_pattern(r"[a-z]+_[a-z_]+").unwrap();
+
+        assert!(protected.contains("pre_tokenize").unwrap());
+        assert!(!protected.contains("pretokenize").unwrap());
+        assert!(!protected.contains("pre_tokenize!").unwrap());
+        assert!(protected.add_pattern("(").is_err());
+
+        let mut protected = ProtectedTerms::new(true);
+
+        protected.add_pattern(r"[a-z]+_[a-z_]+").unwrap();
+
+        assert!(protected.contains("PRE_TOKENIZE").unwrap());
+    }
+
+    #[test]
+    fn test_regex() {
+        let mut protected = ProtectedTerms::new(true);
+
+        assert!(protected.regex().unwrap().is_none());
+
+        protected.insert("C++");
+        protected.insert("New York");
+        protected.insert("New");
+
+        let regex = protected.regex().unwrap().unwrap();
+
+        let message = "new york, C++ and Newton";
+
+        let spans = regex
+            .find_iter(message)
+            .map(|(start, end)| &message[start..end])
+            .collect::<Vec<&str>>();
+
+        assert_eq!(spans, vec!["new york", " C++"]);
+    }
+}
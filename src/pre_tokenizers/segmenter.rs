@@ -1,28 +1,253 @@
+use crate::pre_tokenizers::affix_strip::AffixStrip;
+use crate::pre_tokenizers::cascade::Cascade;
+use crate::pre_tokenizers::custom::Custom;
+use crate::pre_tokenizers::dictionary::Dictionary;
+use crate::pre_tokenizers::ensemble::Ensemble;
+use crate::pre_tokenizers::fallback::Fallback;
 use crate::pre_tokenizers::morfessor::Morfessor;
 use crate::pre_tokenizers::tree_split::TreeSplit;
 
-use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 pub trait Segmenter {
-    fn segment(&self, message: &str) -> Vec<(usize, usize)>;
+    fn segment(&self, message: &str) -> Result<Segmentation, Error>;
+}
+
+// byte offsets of a message alongside a score for each boundary between consecutive offsets
+// unknown words are returned as a single segment, as are known words without boundaries
+// every boundary needs a score, whether the word is known or not
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segmentation {
+    pub offsets: Vec<(usize, usize)>,
+    pub scores: Vec<f64>,
+    pub known: bool,
+    pub source: String, // name of the segmenter that produced the segmentation
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+impl Segmentation {
+    // known segmentation with all boundaries scored 1.0
+    pub fn new(source: &str, offsets: Vec<(usize, usize)>) -> Self {
+        Segmentation {
+            scores: vec![1.0; offsets.len().saturating_sub(1)],
+            offsets,
+            known: true,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn with_scores(source: &str, offsets: Vec<(usize, usize)>, scores: Vec<f64>) -> Self {
+        Segmentation {
+            offsets,
+            scores,
+            known: true,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn unknown(source: &str, message: &str) -> Self {
+        Segmentation {
+            offsets: vec![(0, message.len())],
+            scores: vec![],
+            known: false,
+            source: source.to_string(),
+        }
+    }
+
+    // inner byte boundaries
+    pub fn bounds(&self) -> Vec<usize> {
+        self.offsets
+            .iter()
+            .skip(1)
+            .map(|offsets| offsets.0)
+            .collect()
+    }
+
+    // offsets need to be contiguous, cover the complete message and fall on character boundaries
+    pub fn validate(&self, message: &str) -> Result<(), Error> {
+        let invalid = |reason: String| {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{}: invalid segmentation {:?} of '{}': {}",
+                    self.source, self.offsets, message, reason
+                ),
+            ))
+        };
+
+        let (first, last) = match (self.offsets.first(), self.offsets.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return invalid("empty segmentation".to_string()),
+        };
+
+        if first.0 != 0 || last.1 != message.len() {
+            return invalid("offsets do not cover the message".to_string());
+        }
+
+        for (i, (start, end)) in self.offsets.iter().enumerate() {
+            if start >= end && !message.is_empty() {
+                return invalid(format!("empty segment at {}", start));
+            }
+
+            if i > 0 && self.offsets[i - 1].1 != *start {
+                return invalid(format!("offsets are not contiguous at {}", start));
+            }
+
+            if !message.is_char_boundary(*start) || !message.is_char_boundary(*end) {
+                return invalid(format!("segment {:?} splits a character", (start, end)));
+            }
+        }
+
+        if self.scores.len() + 1 != self.offsets.len() {
+            return invalid(format!(
+                "expected {} scores, found {}",
+                self.offsets.len() - 1,
+                self.scores.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum SegmenterWrapper {
+    Custom(Custom),
     TreeSplit(TreeSplit),
     Morfessor(Morfessor),
+    Fallback(Fallback),
+    Cascade(Cascade),
+    Ensemble(Ensemble),
+    Dictionary(Dictionary),
+    AffixStrip(AffixStrip),
+}
+
+// the serialized form is untagged, variants are told apart by their distinctive keys
+// each variant is deserialized directly so that errors of nested segmenters (e.g. unregistered
+// custom segmenters within a fallback) are not hidden behind a generic untagged error
+fn variant(value: &Value) -> &'static str {
+    let config = value.get("config");
+
+    let config_has = |key: &str| config.and_then(|config| config.get(key)).is_some();
+
+    if value.get("segmenter").is_some_and(Value::is_string) {
+        "custom"
+    } else if value.get("primary").is_some() {
+        "fallback"
+    } else if value.get("coarse").is_some() {
+        "cascade"
+    } else if value.get("members").is_some() {
+        "ensemble"
+    } else if config_has("morph_separator") {
+        "dictionary"
+    } else if config_has("viterbi_smoothing") {
+        "morfessor"
+    } else if config_has("prefixes") || config_has("suffixes") {
+        "affix_strip"
+    } else {
+        "tree_split"
+    }
+}
+
+impl<'de> Deserialize<'de> for SegmenterWrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        let result = match variant(&value) {
+            "custom" => Custom::deserialize(value).map(SegmenterWrapper::Custom),
+            "fallback" => Fallback::deserialize(value).map(SegmenterWrapper::Fallback),
+            "cascade" => Cascade::deserialize(value).map(SegmenterWrapper::Cascade),
+            "ensemble" => Ensemble::deserialize(value).map(SegmenterWrapper::Ensemble),
+            "dictionary" => Dictionary::deserialize(value).map(SegmenterWrapper::Dictionary),
+            "morfessor" => Morfessor::deserialize(value).map(SegmenterWrapper::Morfessor),
+            "affix_strip" => AffixStrip::deserialize(value).map(SegmenterWrapper::AffixStrip),
+            _ => TreeSplit::deserialize(value).map(SegmenterWrapper::TreeSplit),
+        };
+
+        result.map_err(D::Error::custom)
+    }
+}
+
+impl SegmenterWrapper {
+    pub fn name(&self) -> &str {
+        match self {
+            SegmenterWrapper::Custom(cu) => cu.type_name(),
+            SegmenterWrapper::TreeSplit(_) => "tree_split",
+            SegmenterWrapper::Morfessor(_) => "morfessor",
+            SegmenterWrapper::Fallback(_) => "fallback",
+            SegmenterWrapper::Cascade(_) => "cascade",
+            SegmenterWrapper::Ensemble(_) => "ensemble",
+            SegmenterWrapper::Dictionary(_) => "dictionary",
+            SegmenterWrapper::AffixStrip(_) => "affix_strip",
+        }
+    }
 }
 
 impl Segmenter for SegmenterWrapper {
-    fn segment(&self, message: &str) -> Vec<(usize, usize)> {
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
         if message.is_empty() {
-            return vec![(0, message.len())];
+            return Ok(Segmentation::new(self.name(), vec![(0, message.len())]));
         }
 
         match self {
+            SegmenterWrapper::Custom(cu) => cu.segment(message),
             SegmenterWrapper::TreeSplit(ts) => ts.segment(message),
             SegmenterWrapper::Morfessor(mf) => mf.segment(message),
+            SegmenterWrapper::Fallback(fb) => fb.segment(message),
+            SegmenterWrapper::Cascade(cs) => cs.segment(message),
+            SegmenterWrapper::Ensemble(en) => en.segment(message),
+            SegmenterWrapper::Dictionary(dc) => dc.segment(message),
+            SegmenterWrapper::AffixStrip(af) => af.segment(message),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let message = "afghánských";
+
+        assert!(Segmentation::new("test", vec![(0, 11), (11, 13)])
+            .validate(message)
+            .is_ok());
+        assert!(Segmentation::unknown("test", message)
+            .validate(message)
+            .is_ok());
+        assert!(Segmentation::new("test", vec![]).validate(message).is_err());
+        assert!(Segmentation::new("test", vec![(0, 11)])
+            .validate(message)
+            .is_err());
+        assert!(Segmentation::new("test", vec![(0, 9), (11, 13)])
+            .validate(message)
+            .is_err());
+        assert!(Segmentation::new("test", vec![(0, 11), (11, 11), (11, 13)])
+            .validate(message)
+            .is_err());
+        assert!(Segmentation::new("test", vec![(0, 5), (5, 13)])
+            .validate(message)
+            .is_err());
+        assert!(
+            Segmentation::with_scores("test", vec![(0, 11), (11, 13)], vec![])
+                .validate(message)
+                .is_err()
+        );
+
+        // unknown but split without scores
+        let split = Segmentation {
+            offsets: vec![(0, 11), (11, 13)],
+            scores: vec![],
+            known: false,
+            source: "test".to_string(),
+        };
+
+        assert!(split.validate(message).is_err());
+    }
+}
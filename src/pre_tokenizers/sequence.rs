@@ -2,6 +2,7 @@ use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tokenizers::tokenizer::{PreTokenizedString, PreTokenizer, Result};
 
+use crate::pre_tokenizers::trace::{trace_pre_tokenizer, Trace, TraceStage};
 use crate::pre_tokenizers::PreTokenizerWrapper;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -28,6 +29,15 @@ impl Serialize for Sequence {
                 PreTokenizerWrapper::PreByteLevel(_) => {
                     continue;
                 }
+                PreTokenizerWrapper::PreMetaspace(_) => {
+                    continue;
+                }
+                PreTokenizerWrapper::PreWordPiece(_) => {
+                    continue;
+                }
+                PreTokenizerWrapper::LemmaTag(_) => {
+                    continue;
+                }
                 _ => {
                     valid_pretokenizers.push(tokenizer);
                 }
@@ -61,6 +71,39 @@ impl Sequence {
     pub fn get_pre_tokenizers_mut(&mut self) -> &mut [PreTokenizerWrapper] {
         &mut self.pretokenizers
     }
+
+    // see PreTokenizerWrapper::set_training
+    pub fn set_training(&mut self, training: bool) {
+        for pretokenizer in &mut self.pretokenizers {
+            pretokenizer.set_training(training);
+        }
+    }
+
+    // splits after each pre-tokenizer, including the stages of nested PreByteLevel pre-tokenizers
+    pub fn trace(&self, message: &str) -> Result<Trace> {
+        let mut pretokenized = PreTokenizedString::from(message);
+
+        let mut stages = vec![];
+
+        self.trace_into(&mut pretokenized, &mut stages)?;
+
+        Ok(Trace {
+            input: message.to_string(),
+            stages,
+        })
+    }
+
+    pub(crate) fn trace_into(
+        &self,
+        pretokenized: &mut PreTokenizedString,
+        stages: &mut Vec<TraceStage>,
+    ) -> Result<()> {
+        for pretokenizer in &self.pretokenizers {
+            trace_pre_tokenizer(pretokenizer, pretokenized, stages)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl PreTokenizer for Sequence {
@@ -0,0 +1,113 @@
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+
+use crate::pre_tokenizers::affix_strip::{AffixStrip, AffixStripConfig};
+use crate::pre_tokenizers::custom::CustomSegmenter;
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
+use crate::pre_tokenizers::tree_split::{Direction, TreeSplit, TreeSplitConfig};
+use crate::unimorph::registry::UnimorphRegistry;
+
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+// writes content to a file of its own, tests run in parallel and may share file names
+pub(crate) fn write_temp(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "mbpe_test_{}_{}_{}",
+        std::process::id(),
+        TEMP_FILES.fetch_add(1, Ordering::Relaxed),
+        name
+    ));
+
+    std::fs::write(&path, content).unwrap();
+
+    path
+}
+
+// TreeSplit over a single dictionary, languages are the ones queried during segmentation
+pub(crate) fn tree_split<P: AsRef<Path>>(
+    language: &str,
+    dictionary: P,
+    languages: &[&str],
+    direction: Direction,
+) -> TreeSplit {
+    let mut registry = UnimorphRegistry::new("");
+
+    registry.load_from(language, dictionary).unwrap();
+
+    TreeSplit::new(
+        registry,
+        languages
+            .iter()
+            .map(|language| language.to_string())
+            .collect(),
+        TreeSplitConfig {
+            direction,
+            ..TreeSplitConfig::default()
+        },
+    )
+}
+
+// strips "un-" and "-ed", e.g. un|fold|ed
+pub(crate) fn affix_strip_un_ed() -> SegmenterWrapper {
+    SegmenterWrapper::AffixStrip(AffixStrip::new(AffixStripConfig {
+        prefixes: vec!["un".to_string()],
+        suffixes: vec!["ed".to_string()],
+        ..AffixStripConfig::default()
+    }))
+}
+
+// splits with their byte offsets within the original input
+pub(crate) fn offset_splits(pretokenized: &PreTokenizedString) -> Vec<(String, (usize, usize))> {
+    pretokenized
+        .get_splits(OffsetReferential::Original, OffsetType::Byte)
+        .into_iter()
+        .map(|(split, offsets, _)| (split.to_string(), offsets))
+        .collect()
+}
+
+pub(crate) fn splits_with_offsets<P: PreTokenizer>(
+    pre_tokenizer: &P,
+    message: &str,
+) -> Vec<(String, (usize, usize))> {
+    let mut pretokenized = PreTokenizedString::from(message);
+
+    pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+
+    offset_splits(&pretokenized)
+}
+
+pub(crate) fn splits<P: PreTokenizer>(pre_tokenizer: &P, message: &str) -> Vec<String> {
+    splits_with_offsets(pre_tokenizer, message)
+        .into_iter()
+        .map(|(split, _)| split)
+        .collect()
+}
+
+// splits words in the middle, ignoring character boundaries
+#[derive(Deserialize, Serialize)]
+pub(crate) struct Halves;
+
+impl Segmenter for Halves {
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        let half = message.len() / 2;
+
+        Ok(Segmentation::new(
+            "halves",
+            vec![(0, half), (half, message.len())],
+        ))
+    }
+}
+
+impl CustomSegmenter for Halves {
+    fn type_name(&self) -> &str {
+        "halves"
+    }
+
+    fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+
+use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+
+use crate::pre_tokenizers::PreTokenizerWrapper;
+
+// splits after each pre-tokenization stage, see PreByteLevel::trace and Sequence::trace
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    pub input: String,
+    pub stages: Vec<TraceStage>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceStage {
+    pub name: String,
+    pub splits: Vec<TraceSplit>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceSplit {
+    pub text: String,
+    pub offsets: (usize, usize), // byte offsets of the original input
+    pub source: Option<String>,  // segmenter or filter that produced the split
+}
+
+impl TraceStage {
+    // sources are assigned to splits in order, if given
+    pub(crate) fn new(
+        name: &str,
+        pretokenized: &PreTokenizedString,
+        sources: Option<Vec<String>>,
+    ) -> Self {
+        let mut sources = sources.map(|sources| sources.into_iter());
+
+        TraceStage {
+            name: name.to_string(),
+            splits: pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(text, offsets, _)| TraceSplit {
+                    text: text.to_string(),
+                    offsets,
+                    source: sources.as_mut().and_then(|sources| sources.next()),
+                })
+                .collect(),
+        }
+    }
+}
+
+// appends the stages of a single pre-tokenizer, nested stages are prefixed with its name
+pub(crate) fn trace_pre_tokenizer(
+    pretokenizer: &PreTokenizerWrapper,
+    pretokenized: &mut PreTokenizedString,
+    stages: &mut Vec<TraceStage>,
+) -> tokenizers::Result<()> {
+    let name = match pretokenizer {
+        PreTokenizerWrapper::External(_) => "External".to_string(),
+        PreTokenizerWrapper::PreByteLevel(_) => "PreByteLevel".to_string(),
+        PreTokenizerWrapper::PreMetaspace(_) => "PreMetaspace".to_string(),
+        PreTokenizerWrapper::PreWordPiece(_) => "PreWordPiece".to_string(),
+        PreTokenizerWrapper::LemmaTag(_) => "LemmaTag".to_string(),
+        PreTokenizerWrapper::Sequence(_) => "Sequence".to_string(),
+        PreTokenizerWrapper::TokenizersPreTokenizerWrapper(ptw) => serde_json::to_value(ptw)
+            .ok()
+            .and_then(|value| value["type"].as_str().map(|name| name.to_string()))
+            .unwrap_or_else(|| "PreTokenizer".to_string()),
+    };
+
+    let mut nested = vec![];
+
+    match pretokenizer {
+        PreTokenizerWrapper::PreByteLevel(pbl) => pbl.trace_into(pretokenized, &mut nested)?,
+        PreTokenizerWrapper::Sequence(seq) => seq.trace_into(pretokenized, &mut nested)?,
+        _ => {
+            pretokenizer.pre_tokenize(pretokenized)?;
+
+            nested.push(TraceStage::new("", pretokenized, None));
+        }
+    }
+
+    for mut stage in nested {
+        stage.name = if stage.name.is_empty() {
+            name.clone()
+        } else {
+            format!("{} / {}", name, stage.name)
+        };
+
+        stages.push(stage);
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "input: {:?}", self.input)?;
+
+        for stage in &self.stages {
+            writeln!(f, "[{}]", stage.name)?;
+
+            for split in &stage.splits {
+                write!(
+                    f,
+                    "  {:<24} {:>4}..{:<4}",
+                    format!("{:?}", split.text),
+                    split.offsets.0,
+                    split.offsets.1
+                )?;
+
+                match &split.source {
+                    Some(source) => writeln!(f, " {}", source)?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::eligibility::Eligibility;
+    use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::test_utils::affix_strip_un_ed;
+    use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+
+    fn pre_byte_level() -> PreByteLevel {
+        PreByteLevel::new(true, true, affix_strip_un_ed())
+            .with_eligibility(Eligibility {
+                letters_only: true,
+                ..Eligibility::default()
+            })
+            .unwrap()
+    }
+
+    fn texts(stage: &TraceStage) -> Vec<&str> {
+        stage
+            .splits
+            .iter()
+            .map(|split| split.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_pre_byte_level() {
+        let trace = pre_byte_level().trace("unfolded 1999").unwrap();
+
+        let names = trace
+            .stages
+            .iter()
+            .map(|stage| stage.name.as_str())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(names, vec!["prefix space", "regex split", "segmentation"]);
+        assert_eq!(texts(&trace.stages[0]), vec![" unfolded 1999"]);
+        assert_eq!(texts(&trace.stages[1]), vec![" unfolded", " 1999"]);
+        assert_eq!(
+            trace.stages[2].splits,
+            vec![
+                TraceSplit {
+                    text: " un".to_string(),
+                    offsets: (0, 2),
+                    source: Some("affix_strip".to_string()),
+                },
+                TraceSplit {
+                    text: "fold".to_string(),
+                    offsets: (2, 6),
+                    source: Some("affix_strip".to_string()),
+                },
+                TraceSplit {
+                    text: "ed".to_string(),
+                    offsets: (6, 8),
+                    source: Some("affix_strip".to_string()),
+                },
+                TraceSplit {
+                    text: " 1999".to_string(),
+                    offsets: (8, 13),
+                    source: Some("ineligible".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sequence() {
+        let sequence = Sequence::new(vec![
+            PreTokenizerWrapper::from(pre_byte_level()),
+            PreTokenizerWrapper::from(ByteLevel::new(false, true, false)),
+        ]);
+
+        let trace = sequence.trace("unfolded").unwrap();
+
+        let names = trace
+            .stages
+            .iter()
+            .map(|stage| stage.name.as_str())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            names,
+            vec![
+                "PreByteLevel / prefix space",
+                "PreByteLevel / regex split",
+                "PreByteLevel / segmentation",
+                "ByteLevel",
+            ]
+        );
+        assert_eq!(texts(&trace.stages[3]), vec!["Ġun", "fold", "ed"]);
+
+        let printed = trace.to_string();
+
+        assert!(printed.starts_with("input: \"unfolded\"\n[PreByteLevel / prefix space]\n"));
+        assert!(printed.contains("\"Ġun\""));
+        assert!(printed.contains(" affix_strip\n"));
+    }
+}
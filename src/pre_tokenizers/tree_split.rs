@@ -1,82 +1,673 @@
-use std::env;
-use std::path::Path;
+use std::io::Error;
 
 use serde::{Deserialize, Serialize};
 
+use tokenizers::NormalizedString;
+
+use crate::pre_tokenizers::boundary_table::BoundaryTable;
 use crate::pre_tokenizers::pre_byte_level::PreByteLevel;
-use crate::pre_tokenizers::segmenter::{Segmenter, SegmenterWrapper};
+use crate::pre_tokenizers::segmenter::{Segmentation, Segmenter, SegmenterWrapper};
 
+use crate::unimorph::registry::UnimorphRegistry;
 use crate::unimorph::unimorph::Unimorph;
-use crate::utils::radix::split_path;
-
-use radix_tree::{Node, Radix};
+pub use crate::utils::trie::SplitCriterion;
+use crate::utils::trie::Trie;
 
 use crate::utils::offsets;
 
+// dictionaries are loaded from $UNIMORPH_DATA_DIR (default $HOME/.unimorph)
+// languages are given as ISO 639-3 codes and looked up in the given order
+
 pub fn new_pre_tokenizer(
     add_prefix_space: bool,
     use_regex: bool,
-    unimorph_dict: &str,
-) -> PreByteLevel {
-    let mut unimorph = Unimorph::new();
+    languages: &[&str],
+    config: TreeSplitConfig,
+) -> Result<PreByteLevel, Error> {
+    let mut registry = UnimorphRegistry::from_env();
+
+    for language in languages {
+        registry.load(language)?;
+    }
 
-    let home = env::var("HOME").unwrap_or_else(|_| "".to_string());
-    let dict = Path::new(&home).join(Path::new(unimorph_dict));
+    let segmenter = TreeSplit::new(
+        registry,
+        languages
+            .iter()
+            .map(|language| language.to_string())
+            .collect(),
+        config,
+    );
 
-    let _ = unimorph.init(dict.to_str().unwrap());
+    Ok(PreByteLevel::new(
+        add_prefix_space,
+        use_regex,
+        SegmenterWrapper::TreeSplit(segmenter),
+    ))
+}
 
-    let segmenter = TreeSplit { unimorph };
+// precomputed boundaries, see BoundaryTable::build
 
-    PreByteLevel::new(
+pub fn new_pre_tokenizer_from_table(
+    add_prefix_space: bool,
+    use_regex: bool,
+    languages: &[&str],
+    table_path: &str,
+) -> Result<PreByteLevel, Error> {
+    let table = BoundaryTable::load(table_path)?;
+
+    let segmenter = TreeSplit::from_table(
+        table,
+        languages
+            .iter()
+            .map(|language| language.to_string())
+            .collect(),
+    );
+
+    Ok(PreByteLevel::new(
         add_prefix_space,
         use_regex,
         SegmenterWrapper::TreeSplit(segmenter),
-    )
+    ))
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct TreeSplit {
+    // serialized tokenizers predating language selection used Czech
+    #[serde(default = "default_languages")]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub config: TreeSplitConfig,
     #[serde(skip_deserializing, skip_serializing)]
-    pub unimorph: Unimorph,
+    pub registry: UnimorphRegistry,
+    #[serde(skip_deserializing, skip_serializing)]
+    pub table: Option<BoundaryTable>,
 }
 
-impl Segmenter for TreeSplit {
-    fn segment(&self, message: &str) -> Vec<(usize, usize)> {
-        let chars = message.chars().collect::<Vec<char>>();
+fn default_languages() -> Vec<String> {
+    vec!["ces".to_string()]
+}
+
+// fields added later (direction, criterion) default when missing
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct TreeSplitConfig {
+    pub disambiguation: Disambiguation,
+    pub direction: Direction,
+    pub criterion: SplitCriterion,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Direction {
+    // align forms sharing a prefix (suffixal morphology)
+    #[default]
+    Prefix,
+    // align forms sharing a suffix (prefixal morphology)
+    Suffix,
+    // union of prefix and suffix boundaries
+    Both,
+}
+
+// strategies for forms analyzed as belonging to multiple lemmas (homographs)
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Disambiguation {
+    // forms of all paradigms are inserted into a single tree
+    #[default]
+    Merge,
+    // one tree per lemma, keep the tree whose boundaries are shared by most forms of its paradigm
+    Consistent,
+    // one tree per lemma, keep boundaries introduced by all trees
+    Intersect,
+    // one tree per lemma, keep the tree of the lemma with the highest ranked part of speech
+    PosPriority(Vec<String>),
+}
 
-        let mut tree = Node::<char, bool>::new(chars.clone(), Some(true));
+struct Paradigm {
+    pos: Vec<String>,
+    forms: Vec<String>,
+}
+
+impl TreeSplit {
+    pub fn new(
+        registry: UnimorphRegistry,
+        languages: Vec<String>,
+        config: TreeSplitConfig,
+    ) -> Self {
+        TreeSplit {
+            languages,
+            config,
+            registry,
+            table: None,
+        }
+    }
+
+    pub fn from_table(table: BoundaryTable, languages: Vec<String>) -> Self {
+        TreeSplit {
+            languages,
+            config: table.config.clone(),
+            registry: UnimorphRegistry::default(),
+            table: Some(table),
+        }
+    }
+
+    fn split(&self, unimorph: &Unimorph, message: &str) -> Result<Vec<(usize, usize)>, Error> {
+        // dictionary entries are indexed in normalized form
+        // split points are computed on the normalized form and mapped back afterwards
+
+        let normalized = unimorph.normalizer().normalize(message);
+
+        let bounds = self.form_bounds(unimorph, normalized.get());
+
+        bounds_to_original_offsets(&normalized, &bounds)
+    }
 
-        let lemmas = self.unimorph.analyze(message);
+    // character bounds of a normalized form
+    pub(crate) fn form_bounds(&self, unimorph: &Unimorph, form: &str) -> Vec<usize> {
+        let mut lemmas = unimorph.analyze(form);
 
         if lemmas.is_empty() {
-            return vec![(0, message.len())];
+            return vec![];
         }
 
-        for lemma in lemmas {
-            // if !lemma.starts_with(message) {
-            //     continue;
-            // }
+        lemmas.sort();
 
-            for form in self.unimorph.analyze(lemma.as_str()) {
-                if form == message {
-                    continue;
+        let paradigms = lemmas
+            .into_iter()
+            .map(|lemma| Paradigm {
+                pos: unimorph
+                    .features(&lemma, form)
+                    .iter()
+                    .filter_map(|features| features.split(';').next())
+                    .map(|pos| pos.to_string())
+                    .collect(),
+                forms: unimorph.inflect(&lemma, ""),
+            })
+            .collect::<Vec<Paradigm>>();
+
+        self.disambiguate(form, &paradigms)
+    }
+
+    // returns character bounds
+    fn disambiguate(&self, form: &str, paradigms: &[Paradigm]) -> Vec<usize> {
+        if let Disambiguation::Merge = self.config.disambiguation {
+            return self.bounds(form, paradigms.iter().flat_map(|p| p.forms.iter()));
+        }
+
+        let candidates = paradigms
+            .iter()
+            .map(|paradigm| self.bounds(form, paradigm.forms.iter()))
+            .collect::<Vec<Vec<usize>>>();
+
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        match &self.config.disambiguation {
+            Disambiguation::Merge => unreachable!(),
+            Disambiguation::Consistent => {
+                let chars = form.chars().collect::<Vec<char>>();
+
+                // average number of other forms of the paradigm sharing the stem or the ending
+                // a bound introduces, candidates without bounds score zero
+                let consistency = |i: usize| -> f64 {
+                    if candidates[i].is_empty() {
+                        return 0.0;
+                    }
+
+                    let shared = candidates[i]
+                        .iter()
+                        .map(|bound| {
+                            let stem = chars[..*bound].iter().collect::<String>();
+                            let ending = chars[*bound..].iter().collect::<String>();
+
+                            paradigms[i]
+                                .forms
+                                .iter()
+                                .filter(|other| {
+                                    *other != form
+                                        && (other.starts_with(&stem) || other.ends_with(&ending))
+                                })
+                                .count()
+                        })
+                        .sum::<usize>();
+
+                    shared as f64 / candidates[i].len() as f64
+                };
+
+                // highest consistency, fewer bounds and lexicographically smaller lemma win ties
+                let mut best = 0;
+
+                for i in 1..candidates.len() {
+                    let (score, best_score) = (consistency(i), consistency(best));
+
+                    if score > best_score
+                        || (score == best_score && candidates[i].len() < candidates[best].len())
+                    {
+                        best = i;
+                    }
                 }
 
-                if !form.starts_with(message) && !message.starts_with(&form.to_owned()) {
-                    continue;
+                candidates[best].clone()
+            }
+            Disambiguation::Intersect => candidates[0]
+                .iter()
+                .filter(|bound| candidates.iter().all(|other| other.contains(bound)))
+                .cloned()
+                .collect(),
+            Disambiguation::PosPriority(priority) => {
+                let rank = |paradigm: &Paradigm| -> usize {
+                    paradigm
+                        .pos
+                        .iter()
+                        .filter_map(|pos| priority.iter().position(|p| p == pos))
+                        .min()
+                        .unwrap_or(priority.len())
+                };
+
+                let mut best = 0;
+
+                for (i, paradigm) in paradigms.iter().enumerate().skip(1) {
+                    if rank(paradigm) < rank(&paradigms[best]) {
+                        best = i;
+                    }
                 }
 
-                tree.insert(form.chars().collect::<Vec<char>>(), true);
+                candidates[best].clone()
             }
         }
+    }
+
+    fn bounds<'a, I>(&self, form: &str, paradigm: I) -> Vec<usize>
+    where
+        I: Iterator<Item = &'a String> + Clone,
+    {
+        let criterion = &self.config.criterion;
+
+        match self.config.direction {
+            Direction::Prefix => prefix_bounds(form, paradigm, criterion),
+            Direction::Suffix => suffix_bounds(form, paradigm, criterion),
+            Direction::Both => {
+                let mut bounds = prefix_bounds(form, paradigm.clone(), criterion);
+
+                bounds.extend(suffix_bounds(form, paradigm, criterion));
+                bounds.sort();
+                bounds.dedup();
+
+                bounds
+            }
+        }
+    }
+}
+
+// aligns the form with all paradigm forms sharing a prefix
+fn prefix_bounds<'a, I>(form: &str, paradigm: I, criterion: &SplitCriterion) -> Vec<usize>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let chars = form.chars().collect::<Vec<char>>();
+
+    let mut trie = Trie::new();
+
+    trie.insert(chars.iter().cloned());
+
+    for candidate in paradigm {
+        if candidate == form {
+            continue;
+        }
+
+        if !candidate.starts_with(form) && !form.starts_with(&candidate.to_owned()) {
+            continue;
+        }
 
-        // TODO use offset type char and byte instead of converting splits manually
-        //  see tokenizers-0.15.0/src/tokenizer/pre_tokenizer.rs
-        //  bpe pre_tokenizer test -> get_splits has offeset type argument
+        trie.insert(candidate.chars());
+    }
+
+    offsets::offsets_to_bounds(&split_path(&trie, &chars, criterion))
+}
+
+// aligns the reversed form with all reversed paradigm forms sharing a suffix
+fn suffix_bounds<'a, I>(form: &str, paradigm: I, criterion: &SplitCriterion) -> Vec<usize>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let chars = form.chars().rev().collect::<Vec<char>>();
+
+    let mut trie = Trie::new();
+
+    trie.insert(chars.iter().cloned());
+
+    for candidate in paradigm {
+        if candidate == form {
+            continue;
+        }
+
+        if !candidate.ends_with(form) && !form.ends_with(&candidate.to_owned()) {
+            continue;
+        }
+
+        trie.insert(candidate.chars().rev());
+    }
+
+    let length = chars.len();
+
+    offsets::offsets_to_bounds(&split_path(&trie, &chars, criterion))
+        .into_iter()
+        .rev()
+        .map(|bound| length - bound)
+        .collect()
+}
+
+// the form is always part of the trie, inconsistencies fall back to the whole form
+fn split_path(trie: &Trie, path: &[char], criterion: &SplitCriterion) -> Vec<(usize, usize)> {
+    trie.split_path(path, criterion)
+        .unwrap_or_else(|_| vec![(0, path.len())])
+}
+
+// TODO use offset type char and byte instead of converting splits manually
+//  see tokenizers-0.15.0/src/tokenizer/pre_tokenizer.rs
+//  bpe pre_tokenizer test -> get_splits has offeset type argument
+
+// decomposed unicode sequences probably result in invalid character offsets
+// see utils/offsets.rs for details on differing unicode representations
+
+pub(crate) fn bounds_to_original_offsets(
+    normalized: &NormalizedString,
+    bounds: &[usize],
+) -> Result<Vec<(usize, usize)>, Error> {
+    let form = normalized.get();
+
+    Ok(offsets::normalized_to_original_offsets(
+        normalized,
+        offsets::scalar_to_byte_offsets(
+            form,
+            offsets::bounds_to_offsets(bounds, form.chars().count()),
+        )?,
+    ))
+}
+
+impl Segmenter for TreeSplit {
+    // words missing from all dictionaries are unknown
+    fn segment(&self, message: &str) -> Result<Segmentation, Error> {
+        let offsets = match &self.table {
+            Some(table) => table.segment(&self.languages, message)?,
+            None => match self.registry.lookup(&self.languages, message) {
+                Some(unimorph) => Some(self.split(unimorph, message)?),
+                None => None,
+            },
+        };
+
+        match offsets {
+            Some(offsets) => Ok(Segmentation::new("tree_split", offsets)),
+            None => Ok(Segmentation::unknown("tree_split", message)),
+        }
+    }
+}
+
+#[test]
+fn test_segment_normalized() {
+    use crate::unimorph::normalizer::{LookupNormalizer, UnicodeForm};
+    use std::collections::HashMap;
+
+    let normalizer = LookupNormalizer::new(Some(UnicodeForm::Nfc), true, HashMap::new());
+
+    let mut registry = UnimorphRegistry::with_normalizer("", normalizer);
+
+    registry.load_from("ces", "ces_afghansky.tsv").unwrap();
+
+    let segmenter = TreeSplit::new(
+        registry,
+        vec!["ces".to_string()],
+        TreeSplitConfig::default(),
+    );
+
+    assert_eq!(
+        segmenter.segment("afghánských").unwrap().offsets,
+        vec![(0, 11), (11, 13)]
+    );
+    assert_eq!(
+        segmenter.segment("Afghánských").unwrap().offsets,
+        vec![(0, 11), (11, 13)]
+    );
 
-        // decomposed unicode sequences probably result in invalid character offsets
-        // see utils/offsets.rs for details on differing unicode representations
+    // decomposed input -> afgha\u{0301}nsky\u{0301}ch
+    assert_eq!(
+        segmenter
+            .segment("afgha\u{0301}nsky\u{0301}ch")
+            .unwrap()
+            .offsets,
+        vec![(0, 13), (13, 15)]
+    );
+}
+
+#[test]
+fn test_segment_languages() {
+    let mut registry = UnimorphRegistry::new("");
+
+    registry.load_from("ces", "ces_afghansky.tsv").unwrap();
+
+    let segmenter = TreeSplit::new(
+        registry.clone(),
+        vec!["deu".to_string(), "ces".to_string()],
+        TreeSplitConfig::default(),
+    );
+
+    assert_eq!(
+        segmenter.segment("afghánských").unwrap().offsets,
+        vec![(0, 11), (11, 13)]
+    );
 
-        offsets::scalar_to_byte_offsets(message, split_path(tree, chars))
+    let segmenter = TreeSplit::new(
+        registry,
+        vec!["deu".to_string()],
+        TreeSplitConfig::default(),
+    );
+
+    assert_eq!(
+        segmenter.segment("afghánských").unwrap().offsets,
+        vec![(0, 13)]
+    );
+    assert!(!segmenter.segment("afghánských").unwrap().known);
+}
+
+#[cfg(test)]
+fn paradigm(pos: &str, forms: &[&str]) -> Paradigm {
+    Paradigm {
+        pos: vec![pos.to_string()],
+        forms: forms.iter().map(|form| form.to_string()).collect(),
     }
 }
+
+#[test]
+fn test_disambiguate() {
+    // "leaves" -> leaf (N) and leave (V)
+    let paradigms = vec![
+        paradigm("N", &["leaf", "leaves"]),
+        paradigm("V", &["leave", "leaves", "leaving", "left"]),
+    ];
+
+    let tree_split = |disambiguation: Disambiguation| TreeSplit {
+        languages: vec![],
+        config: TreeSplitConfig {
+            disambiguation,
+            ..TreeSplitConfig::default()
+        },
+        registry: UnimorphRegistry::default(),
+        table: None,
+    };
+
+    assert_eq!(
+        tree_split(Disambiguation::Merge).disambiguate("leaves", &paradigms),
+        vec![5]
+    );
+    // leave|s is shared by "leave", leaf has no bounds, fewest bounds would pick leaf
+    assert_eq!(
+        tree_split(Disambiguation::Consistent).disambiguate("leaves", &paradigms),
+        vec![5]
+    );
+    assert_eq!(
+        tree_split(Disambiguation::Intersect).disambiguate("leaves", &paradigms),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        tree_split(Disambiguation::PosPriority(vec![
+            "V".to_string(),
+            "N".to_string()
+        ]))
+        .disambiguate("leaves", &paradigms),
+        vec![5]
+    );
+    assert_eq!(
+        tree_split(Disambiguation::PosPriority(vec![
+            "N".to_string(),
+            "V".to_string()
+        ]))
+        .disambiguate("leaves", &paradigms),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn test_disambiguate_consistent() {
+    // one bound per lemma, ne|su is shared by two other forms and nes|u by one
+    let paradigms = vec![
+        paradigm("V", &["nes", "nesu"]),
+        paradigm("V", &["ne", "nesu", "nemám"]),
+    ];
+
+    let tree_split = TreeSplit {
+        languages: vec![],
+        config: TreeSplitConfig {
+            disambiguation: Disambiguation::Consistent,
+            ..TreeSplitConfig::default()
+        },
+        registry: UnimorphRegistry::default(),
+        table: None,
+    };
+
+    let candidates = paradigms
+        .iter()
+        .map(|paradigm| tree_split.bounds("nesu", paradigm.forms.iter()))
+        .collect::<Vec<Vec<usize>>>();
+
+    assert_eq!(candidates, vec![vec![3], vec![2]]);
+    // fewest bounds would keep the first lemma
+    assert_eq!(tree_split.disambiguate("nesu", &paradigms), vec![2]);
+}
+
+#[test]
+fn test_direction() {
+    let paradigms = vec![paradigm("ADJ", &["dobrými", "nedobrý"])];
+
+    let tree_split = |direction: Direction| TreeSplit {
+        languages: vec![],
+        config: TreeSplitConfig {
+            direction,
+            ..TreeSplitConfig::default()
+        },
+        registry: UnimorphRegistry::default(),
+        table: None,
+    };
+
+    // ne|dobrý|mi
+    assert_eq!(
+        tree_split(Direction::Prefix).disambiguate("nedobrými", &paradigms),
+        vec![7]
+    );
+    assert_eq!(
+        tree_split(Direction::Suffix).disambiguate("nedobrými", &paradigms),
+        vec![2]
+    );
+    assert_eq!(
+        tree_split(Direction::Both).disambiguate("nedobrými", &paradigms),
+        vec![2, 7]
+    );
+}
+
+#[test]
+fn test_criterion() {
+    let paradigms = vec![paradigm("NOUN", &["foo", "foobar", "foobarbaz"])];
+
+    let tree_split = |criterion: SplitCriterion| TreeSplit {
+        languages: vec![],
+        config: TreeSplitConfig {
+            criterion,
+            ..TreeSplitConfig::default()
+        },
+        registry: UnimorphRegistry::default(),
+        table: None,
+    };
+
+    // foo|bar|baz
+    assert_eq!(
+        tree_split(SplitCriterion::default()).disambiguate("foobarbaz", &paradigms),
+        vec![3, 6]
+    );
+    assert_eq!(
+        tree_split(SplitCriterion {
+            min_suffix_length: 4,
+            ..SplitCriterion::default()
+        })
+        .disambiguate("foobarbaz", &paradigms),
+        vec![3]
+    );
+    assert_eq!(
+        tree_split(SplitCriterion {
+            min_stem_length: 4,
+            ..SplitCriterion::default()
+        })
+        .disambiguate("foobarbaz", &paradigms),
+        vec![6]
+    );
+    assert_eq!(
+        tree_split(SplitCriterion {
+            max_boundaries: Some(1),
+            ..SplitCriterion::default()
+        })
+        .disambiguate("foobarbaz", &paradigms),
+        vec![3]
+    );
+    assert_eq!(
+        tree_split(SplitCriterion {
+            min_children: 3,
+            ..SplitCriterion::default()
+        })
+        .disambiguate("foobarbaz", &paradigms),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn test_deserialize_baseline() {
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    // tokenizers serialized before languages and config were added
+    let json = r#"{"type":"PreByteLevel","add_prefix_space":true,"use_regex":true,"segmenter":{}}"#;
+
+    let expected = PreByteLevel::new(
+        true,
+        true,
+        SegmenterWrapper::TreeSplit(TreeSplit::new(
+            UnimorphRegistry::default(),
+            vec!["ces".to_string()],
+            TreeSplitConfig::default(),
+        )),
+    );
+
+    assert_eq!(
+        serde_json::from_str::<PreTokenizerWrapper>(json).unwrap(),
+        PreTokenizerWrapper::from(expected)
+    );
+}
+
+#[test]
+fn test_deserialize_config() {
+    let config = serde_json::from_str::<TreeSplitConfig>(r#"{"disambiguation":"Consistent"}"#);
+
+    assert_eq!(
+        config.unwrap(),
+        TreeSplitConfig {
+            disambiguation: Disambiguation::Consistent,
+            ..Default::default()
+        }
+    );
+}
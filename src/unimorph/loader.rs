@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    // malformed rows abort loading
+    Strict,
+    // malformed rows are skipped and recorded in the ingestion report
+    #[default]
+    Lenient,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct LoaderConfig {
+    pub has_headers: bool,
+    pub mode: LoadMode,
+    pub skip_multiword: bool,
+}
+
+impl LoaderConfig {
+    pub fn strict() -> Self {
+        LoaderConfig {
+            mode: LoadMode::Strict,
+            ..LoaderConfig::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct IngestionReport {
+    pub rows: usize,
+    pub entries: usize,
+    pub skipped: Vec<(u64, String)>, // line number and reason
+    pub duplicates: Vec<u64>,        // line numbers of repeated (lemma, form, features) triples
+    pub multiword: Vec<u64>,         // line numbers of forms containing whitespace
+}
+
+impl fmt::Display for IngestionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} rows, {} entries, {} skipped, {} duplicates, {} multiword forms",
+            self.rows,
+            self.entries,
+            self.skipped.len(),
+            self.duplicates.len(),
+            self.multiword.len()
+        )?;
+
+        for (line, reason) in &self.skipped {
+            writeln!(f, "skipped line {}: {}", line, reason)?;
+        }
+
+        Ok(())
+    }
+}
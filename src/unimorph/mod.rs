@@ -1 +1,5 @@
+pub mod loader;
+pub mod normalizer;
+pub mod registry;
+#[allow(clippy::module_inception)]
 pub mod unimorph;
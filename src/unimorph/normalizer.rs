@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use tokenizers::NormalizedString;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnicodeForm {
+    Nfc,
+    Nfd,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct LookupNormalizer {
+    pub unicode: Option<UnicodeForm>,
+    pub lowercase: bool,
+    pub fold: HashMap<char, char>,
+}
+
+impl LookupNormalizer {
+    pub fn new(unicode: Option<UnicodeForm>, lowercase: bool, fold: HashMap<char, char>) -> Self {
+        LookupNormalizer {
+            unicode,
+            lowercase,
+            fold,
+        }
+    }
+
+    // folding table mapping typographic apostrophe variants onto U+0027
+    pub fn apostrophes() -> HashMap<char, char> {
+        HashMap::from([
+            ('\u{2019}', '\''), // right single quotation mark
+            ('\u{2018}', '\''), // left single quotation mark
+            ('\u{02BC}', '\''), // modifier letter apostrophe
+            ('\u{02B9}', '\''), // modifier letter prime
+            ('\u{0060}', '\''), // grave accent
+            ('\u{00B4}', '\''), // acute accent
+            ('\u{2032}', '\''), // prime
+        ])
+    }
+
+    // true if normalize leaves every form unchanged
+    pub fn is_identity(&self) -> bool {
+        self.unicode.is_none() && !self.lowercase && self.fold.is_empty()
+    }
+
+    // returns the normalized form alongside its alignments to the original form
+    // use utils::offsets::normalized_to_original_offsets to map offsets back
+    pub fn normalize(&self, form: &str) -> NormalizedString {
+        let mut normalized = NormalizedString::from(form);
+
+        match self.unicode {
+            Some(UnicodeForm::Nfc) => {
+                normalized.nfc();
+            }
+            Some(UnicodeForm::Nfd) => {
+                normalized.nfd();
+            }
+            None => {}
+        }
+
+        if self.lowercase {
+            normalized.lowercase();
+        }
+
+        if !self.fold.is_empty() {
+            normalized.map(|c| *self.fold.get(&c).unwrap_or(&c));
+        }
+
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_identity() {
+        assert!(LookupNormalizer::default().is_identity());
+        assert!(!LookupNormalizer::new(None, true, HashMap::new()).is_identity());
+        assert!(!LookupNormalizer::new(None, false, LookupNormalizer::apostrophes()).is_identity());
+    }
+
+    #[test]
+    fn test_normalize_lowercase() {
+        let normalizer = LookupNormalizer::new(None, true, HashMap::new());
+
+        assert_eq!(normalizer.normalize("Afghánský").get(), "afghánský");
+    }
+
+    #[test]
+    fn test_normalize_nfc() {
+        let normalizer = LookupNormalizer::new(Some(UnicodeForm::Nfc), false, HashMap::new());
+
+        assert_eq!(normalizer.normalize("a\u{0301}").get(), "\u{00E1}");
+    }
+
+    #[test]
+    fn test_normalize_fold() {
+        let normalizer = LookupNormalizer::new(None, false, LookupNormalizer::apostrophes());
+
+        assert_eq!(normalizer.normalize("don\u{2019}t").get(), "don't");
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::unimorph::loader::{IngestionReport, LoaderConfig};
+use crate::unimorph::normalizer::LookupNormalizer;
+use crate::unimorph::unimorph::Unimorph;
+
+pub const DATA_DIR_ENV: &str = "UNIMORPH_DATA_DIR";
+
+// dictionaries are expected to follow the layout of the unimorph language repositories
+// e.g. <data_dir>/ces/ces, <data_dir>/deu/deu and <data_dir>/fin/fin
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct UnimorphRegistry {
+    data_dir: PathBuf,
+    normalizer: LookupNormalizer,
+    loader: LoaderConfig,
+    languages: HashMap<String, Unimorph>,
+    reports: HashMap<String, IngestionReport>,
+}
+
+impl UnimorphRegistry {
+    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
+        UnimorphRegistry {
+            data_dir: data_dir.as_ref().to_path_buf(),
+            normalizer: LookupNormalizer::default(),
+            loader: LoaderConfig::default(),
+            languages: HashMap::new(),
+            reports: HashMap::new(),
+        }
+    }
+
+    pub fn with_normalizer<P: AsRef<Path>>(data_dir: P, normalizer: LookupNormalizer) -> Self {
+        UnimorphRegistry {
+            normalizer,
+            ..UnimorphRegistry::new(data_dir)
+        }
+    }
+
+    // $UNIMORPH_DATA_DIR falling back to $HOME/.unimorph
+    pub fn from_env() -> Self {
+        if let Ok(data_dir) = env::var(DATA_DIR_ENV) {
+            return UnimorphRegistry::new(data_dir);
+        }
+
+        let home = env::var("HOME").unwrap_or_else(|_| "".to_string());
+
+        UnimorphRegistry::new(Path::new(&home).join(".unimorph"))
+    }
+
+    // applies to dictionaries loaded afterwards
+    pub fn set_loader(&mut self, loader: LoaderConfig) {
+        self.loader = loader;
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn path(&self, language: &str) -> PathBuf {
+        self.data_dir.join(language).join(language)
+    }
+
+    pub fn load(&mut self, language: &str) -> Result<&IngestionReport, Error> {
+        let path = self.path(language);
+
+        self.load_from(language, path)
+    }
+
+    pub fn load_from<P: AsRef<Path>>(
+        &mut self,
+        language: &str,
+        dict: P,
+    ) -> Result<&IngestionReport, Error> {
+        validate_language(language)?;
+
+        let dict = dict.as_ref().to_str().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "dictionary path is not valid UTF-8",
+            )
+        })?;
+
+        let mut unimorph = Unimorph::with_normalizer(self.normalizer.clone());
+
+        unimorph.set_language(language);
+
+        let report = unimorph.load(dict, &self.loader)?;
+
+        self.languages.insert(language.to_string(), unimorph);
+
+        self.reports.insert(language.to_string(), report);
+
+        Ok(&self.reports[language])
+    }
+
+    pub fn report(&self, language: &str) -> Option<&IngestionReport> {
+        self.reports.get(language)
+    }
+
+    pub fn get(&self, language: &str) -> Option<&Unimorph> {
+        self.languages.get(language)
+    }
+
+    pub fn contains(&self, language: &str) -> bool {
+        self.languages.contains_key(language)
+    }
+
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages = self.languages.keys().cloned().collect::<Vec<String>>();
+
+        languages.sort();
+
+        languages
+    }
+
+    pub fn inflect(&self, language: &str, lemma: &str, features: &str) -> Vec<String> {
+        match self.languages.get(language) {
+            Some(unimorph) => unimorph.inflect(lemma, features),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn analyze(&self, language: &str, form: &str) -> Vec<String> {
+        match self.languages.get(language) {
+            Some(unimorph) => unimorph.analyze(form),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn features(&self, language: &str, lemma: &str, form: &str) -> Vec<String> {
+        match self.languages.get(language) {
+            Some(unimorph) => unimorph.features(lemma, form),
+            None => Vec::new(),
+        }
+    }
+
+    // returns the first dictionary in the given order that knows the form
+    pub fn lookup(&self, languages: &[String], form: &str) -> Option<&Unimorph> {
+        languages
+            .iter()
+            .filter_map(|language| self.languages.get(language))
+            .find(|unimorph| !unimorph.analyze(form).is_empty())
+    }
+}
+
+// ISO 639-3 codes consist of three lowercase ascii letters
+fn validate_language(language: &str) -> Result<(), Error> {
+    if language.len() == 3 && language.chars().all(|c| c.is_ascii_lowercase()) {
+        return Ok(());
+    }
+
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!("invalid ISO 639-3 language code '{}'", language),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let registry = UnimorphRegistry::new("/data/unimorph");
+
+        assert_eq!(
+            registry.path("ces"),
+            PathBuf::from("/data/unimorph/ces/ces")
+        );
+    }
+
+    #[test]
+    fn test_load_invalid_language() {
+        let mut registry = UnimorphRegistry::new("");
+
+        assert!(registry.load_from("cs", "ces_afghansky.tsv").is_err());
+        assert!(registry.load_from("CES", "ces_afghansky.tsv").is_err());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut registry = UnimorphRegistry::new("");
+
+        registry.load_from("ces", "ces_afghansky.tsv").unwrap();
+
+        assert_eq!(registry.languages(), vec!["ces"]);
+        assert_eq!(registry.report("ces").unwrap().entries, 35);
+        assert_eq!(registry.get("ces").unwrap().language(), "ces");
+        assert_eq!(registry.analyze("ces", "afghánskou"), vec!["afghánský"]);
+        assert!(registry.analyze("deu", "afghánskou").is_empty());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut registry = UnimorphRegistry::new("");
+
+        registry.load_from("ces", "ces_afghansky.tsv").unwrap();
+
+        let languages = vec!["deu".to_string(), "ces".to_string()];
+
+        assert_eq!(
+            registry
+                .lookup(&languages, "afghánskou")
+                .unwrap()
+                .language(),
+            "ces"
+        );
+        assert!(registry.lookup(&languages, "foo").is_none());
+    }
+}
@@ -1,7 +1,12 @@
 use csv::ReaderBuilder;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
+use std::io::{Error, ErrorKind};
+
+use crate::unimorph::loader::{IngestionReport, LoadMode, LoaderConfig};
+use crate::unimorph::normalizer::LookupNormalizer;
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Unimorph {
@@ -9,6 +14,7 @@ pub struct Unimorph {
     inflect: HashMap<String, HashMap<String, ()>>,
     analyze: HashMap<String, HashMap<String, ()>>,
     features: HashMap<String, HashMap<String, Vec<String>>>,
+    normalizer: LookupNormalizer,
 }
 
 impl Unimorph {
@@ -18,42 +24,141 @@ impl Unimorph {
             inflect: HashMap::new(),
             analyze: HashMap::new(),
             features: HashMap::new(),
+            normalizer: LookupNormalizer::default(),
+        }
+    }
+
+    // lookup normalization needs to be set before calling init
+    // since dictionary entries are indexed in normalized form
+    pub fn with_normalizer(normalizer: LookupNormalizer) -> Self {
+        Unimorph {
+            normalizer,
+            ..Unimorph::new()
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    // ISO 639-3 code of the dictionary language
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    pub fn normalizer(&self) -> &LookupNormalizer {
+        &self.normalizer
+    }
+
+    // the identity normalizer leaves keys untouched, no need to build alignments
+    fn normalize<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        if self.normalizer.is_identity() {
+            return Cow::Borrowed(key);
         }
+
+        Cow::Owned(self.normalizer.normalize(key).get().to_string())
     }
 
     pub fn init(&mut self, dict: &str) -> Result<(), std::io::Error> {
+        self.load(dict, &LoaderConfig::default()).map(|_| ())
+    }
+
+    pub fn load(
+        &mut self,
+        dict: &str,
+        config: &LoaderConfig,
+    ) -> Result<IngestionReport, std::io::Error> {
         let file = File::open(dict)?;
 
-        let mut rdr = ReaderBuilder::new().delimiter(b'\t').from_reader(file);
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(config.has_headers)
+            .flexible(true)
+            .quoting(false)
+            .from_reader(file);
+
+        let mut report = IngestionReport::default();
 
         for result in rdr.records() {
             let record = result?;
 
-            let lemma = record[0].to_owned();
-            let form = record[1].to_string();
+            let line = record.position().map_or(0, |position| position.line());
+
+            report.rows += 1;
+
+            let reason = if record.len() < 3 {
+                Some(format!("expected 3 fields, found {}", record.len()))
+            } else if record[0].is_empty() {
+                Some("empty lemma".to_string())
+            } else if record[1].is_empty() {
+                Some("empty form".to_string())
+            } else if record[2].is_empty() {
+                Some("empty features".to_string())
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                if config.mode == LoadMode::Strict {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("{}: line {}: {}", dict, line, reason),
+                    ));
+                }
+
+                report.skipped.push((line, reason));
+
+                continue;
+            }
+
+            if record[1].contains(char::is_whitespace) {
+                report.multiword.push(line);
+
+                if config.skip_multiword {
+                    report.skipped.push((line, "multiword form".to_string()));
+
+                    continue;
+                }
+            }
+
+            let lemma = self.normalize(&record[0]).into_owned();
+            let form = self.normalize(&record[1]).into_owned();
             let features = record[2].to_string();
 
+            let fss = self
+                .features
+                .entry(lemma.clone())
+                .or_default()
+                .entry(form.clone())
+                .or_default();
+
+            if fss.contains(&features) {
+                report.duplicates.push(line);
+
+                continue;
+            }
+
+            fss.push(features);
+
             self.inflect
                 .entry(lemma.clone())
-                .or_insert_with(HashMap::new)
+                .or_default()
                 .insert(form.clone(), ());
             self.analyze
                 .entry(form.clone())
-                .or_insert_with(HashMap::new)
+                .or_default()
                 .insert(lemma.clone(), ());
-            self.features
-                .entry(lemma.clone())
-                .or_insert_with(HashMap::new)
-                .entry(form.clone())
-                .or_insert_with(Vec::new)
-                .push(features);
+
+            report.entries += 1;
         }
 
-        Ok(())
+        Ok(report)
     }
 
     pub fn inflect(&self, lemma: &str, features: &str) -> Vec<String> {
-        let forms = match self.inflect.get(lemma) {
+        let lemma = self.normalize(lemma);
+
+        let forms = match self.inflect.get(lemma.as_ref()) {
             Some(forms) => forms,
             None => return Vec::new(),
         };
@@ -67,7 +172,12 @@ impl Unimorph {
                 continue;
             }
 
-            let fss = self.features.get(lemma).unwrap().get(form).unwrap();
+            let fss = self
+                .features
+                .get(lemma.as_ref())
+                .unwrap()
+                .get(form)
+                .unwrap();
 
             for fs in fss {
                 if fs == features {
@@ -76,11 +186,11 @@ impl Unimorph {
             }
         }
 
-        return result;
+        result
     }
 
     pub fn analyze(&self, form: &str) -> Vec<String> {
-        let lemmas = match self.analyze.get(form) {
+        let lemmas = match self.analyze.get(self.normalize(form).as_ref()) {
             Some(lemmas) => lemmas,
             None => return Vec::new(),
         };
@@ -91,17 +201,22 @@ impl Unimorph {
             result.push(lemma.clone());
         }
 
-        return result;
+        result
+    }
+
+    // all forms of the dictionary in normalized form
+    pub fn forms(&self) -> impl Iterator<Item = &String> {
+        self.analyze.keys()
     }
 
     pub fn features(&self, lemma: &str, form: &str) -> Vec<String> {
-        return match self.features.get(lemma) {
-            Some(forms) => match forms.get(form) {
+        match self.features.get(self.normalize(lemma).as_ref()) {
+            Some(forms) => match forms.get(self.normalize(form).as_ref()) {
                 Some(features) => features.clone(),
                 None => Vec::new(),
             },
             None => Vec::new(),
-        };
+        }
     }
 }
 
@@ -171,3 +286,110 @@ fn test_features() {
 
     assert_eq!(features, vec!["ADJ;ACC;FEM;SG"]);
 }
+
+#[test]
+fn test_analyze_normalized() {
+    let normalizer = LookupNormalizer::new(
+        Some(crate::unimorph::normalizer::UnicodeForm::Nfc),
+        true,
+        HashMap::new(),
+    );
+
+    let mut unimorph = Unimorph::with_normalizer(normalizer);
+
+    unimorph.init("ces_afghansky.tsv").unwrap();
+
+    assert_eq!(unimorph.analyze("Afghánskou"), vec!["afghánský"]);
+    assert_eq!(unimorph.analyze("afgha\u{0301}nskou"), vec!["afghánský"]);
+    assert!(unimorph.analyze("afgha\u{0301}nskou_").is_empty());
+}
+
+#[test]
+fn test_features_normalized() {
+    let normalizer = LookupNormalizer::new(None, true, HashMap::new());
+
+    let mut unimorph = Unimorph::with_normalizer(normalizer);
+
+    unimorph.init("ces_afghansky.tsv").unwrap();
+
+    assert_eq!(
+        unimorph.features("Afghánský", "AFGHÁNSKOU"),
+        vec!["ADJ;ACC;FEM;SG"]
+    );
+}
+
+#[cfg(test)]
+fn write_dict(name: &str, content: &str) -> String {
+    crate::pre_tokenizers::test_utils::write_temp(name, content)
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_load_report() {
+    let dict = write_dict(
+        "load_report.tsv",
+        "lemma\tform\tfeatures\n\
+         foo\tfoos\tN;PL\n\
+         foo\n\
+         foo\tfoos\tN;PL\n\
+         foo\tfoo\t\n\
+         foo bar\tfoo bars\tN;PL\n",
+    );
+
+    let mut unimorph = Unimorph::new();
+
+    let config = LoaderConfig {
+        has_headers: true,
+        ..LoaderConfig::default()
+    };
+
+    let report = unimorph.load(&dict, &config).unwrap();
+
+    assert_eq!(report.rows, 5);
+    assert_eq!(report.entries, 2);
+    assert_eq!(
+        report.skipped,
+        vec![
+            (3, "expected 3 fields, found 1".to_string()),
+            (5, "empty features".to_string())
+        ]
+    );
+    assert_eq!(report.duplicates, vec![4]);
+    assert_eq!(report.multiword, vec![6]);
+
+    assert_eq!(unimorph.analyze("foos"), vec!["foo"]);
+    assert_eq!(unimorph.analyze("foo bars"), vec!["foo bar"]);
+}
+
+#[test]
+fn test_load_strict() {
+    let dict = write_dict("load_strict.tsv", "foo\tfoos\tN;PL\nfoo\tfoos\n");
+
+    let mut unimorph = Unimorph::new();
+
+    let err = unimorph.load(&dict, &LoaderConfig::strict()).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err
+        .to_string()
+        .ends_with("line 2: expected 3 fields, found 2"));
+}
+
+#[test]
+fn test_load_without_headers() {
+    let mut unimorph = Unimorph::new();
+
+    let report = unimorph
+        .load("ces_afghansky.tsv", &LoaderConfig::default())
+        .unwrap();
+
+    // first row must not be consumed as header
+    assert_eq!(report.rows, 35);
+    assert_eq!(report.entries, 35);
+    assert_eq!(
+        unimorph.features("afghánský", "afghánská")[0],
+        "ADJ;ACC;NEUT;PL"
+    );
+}
@@ -1,2 +1,2 @@
 pub mod offsets;
-pub mod radix;
+pub mod trie;
@@ -1,3 +1,8 @@
+use std::io::{Error, ErrorKind};
+
+use tokenizers::normalizer::Range;
+use tokenizers::NormalizedString;
+
 pub fn collect_scalar_offsets(segments: Vec<String>) -> Vec<(usize, usize)> {
     let mut character_offsets: Vec<(usize, usize)> = vec![];
 
@@ -11,17 +16,26 @@ pub fn collect_scalar_offsets(segments: Vec<String>) -> Vec<(usize, usize)> {
         index += length;
     }
 
-    return character_offsets;
+    character_offsets
 }
 
 pub fn scalar_to_byte_offsets(
     message: &str,
     character_offsets: Vec<(usize, usize)>,
-) -> Vec<(usize, usize)> {
+) -> Result<Vec<(usize, usize)>, Error> {
     let chars = message.chars().collect::<Vec<char>>(); // unicode scalar values
 
-    if character_offsets[character_offsets.len() - 1].1 < chars.len() {
-        panic!("invalid character offsets")
+    if character_offsets
+        .last()
+        .is_none_or(|last| last.1 != chars.len())
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid character offsets {:?} for '{}'",
+                character_offsets, message
+            ),
+        ));
     }
 
     let mut byte_offsets: Vec<(usize, usize)> = vec![];
@@ -29,6 +43,13 @@ pub fn scalar_to_byte_offsets(
     let mut index = 0;
 
     for offsets in character_offsets {
+        if offsets.0 > offsets.1 || offsets.1 > chars.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid character offsets {:?} for '{}'", offsets, message),
+            ));
+        }
+
         let length = chars[offsets.0..offsets.1]
             .iter()
             .map(|c| c.len_utf8())
@@ -39,7 +60,7 @@ pub fn scalar_to_byte_offsets(
         index += length;
     }
 
-    return byte_offsets;
+    Ok(byte_offsets)
 }
 
 pub fn unicode_scalar_bounds(message: &str) -> Vec<usize> {
@@ -55,7 +76,74 @@ pub fn unicode_scalar_bounds(message: &str) -> Vec<usize> {
         index += length;
     }
 
-    return bounds;
+    bounds
+}
+
+// inner bounds (excluding start and end) of contiguous offsets
+pub fn offsets_to_bounds(offsets: &[(usize, usize)]) -> Vec<usize> {
+    let end = offsets.iter().map(|offsets| offsets.1).max().unwrap_or(0);
+
+    let mut bounds: Vec<usize> = vec![];
+
+    for offsets in offsets {
+        if offsets.1 == 0 || offsets.1 >= end || bounds.last() == Some(&offsets.1) {
+            continue;
+        }
+
+        bounds.push(offsets.1);
+    }
+
+    bounds
+}
+
+pub fn bounds_to_offsets(bounds: &[usize], length: usize) -> Vec<(usize, usize)> {
+    let mut offsets: Vec<(usize, usize)> = vec![];
+
+    let mut index = 0;
+
+    for bound in bounds {
+        offsets.push((index, *bound));
+
+        index = *bound;
+    }
+
+    offsets.push((index, length));
+
+    offsets
+}
+
+// maps byte offsets within the normalized form to byte offsets within the original form
+// boundaries falling inside of an original character (e.g. between a base character and
+// a combining mark that got composed) snap to the start of that character
+
+pub fn normalized_to_original_offsets(
+    normalized: &NormalizedString,
+    normalized_offsets: Vec<(usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let original = normalized.get_original();
+
+    let mut bounds: Vec<usize> = vec![];
+
+    for offsets in normalized_offsets {
+        if offsets.0 == 0 || offsets.0 == offsets.1 {
+            continue;
+        }
+
+        let bound = match normalized.convert_offsets(Range::Normalized(offsets.0..offsets.1)) {
+            Some(range) => range.start,
+            None => continue,
+        };
+
+        if bound == 0 || bound >= original.len() || !original.is_char_boundary(bound) {
+            continue;
+        }
+
+        if bounds.last().is_none_or(|last| *last < bound) {
+            bounds.push(bound);
+        }
+    }
+
+    bounds_to_offsets(&bounds, original.len())
 }
 
 #[cfg(test)]
@@ -75,17 +163,28 @@ mod tests {
 
     #[test]
     fn test_scalar_to_byte_offsets_composed() {
-        assert_eq!(scalar_to_byte_offsets(COMPOSED, vec![(0, 1)]), vec![(0, 2)]);
+        assert_eq!(
+            scalar_to_byte_offsets(COMPOSED, vec![(0, 1)]).unwrap(),
+            vec![(0, 2)]
+        );
     }
 
     #[test]
     fn test_scalar_to_byte_offset_decomposed() {
         assert_eq!(
-            scalar_to_byte_offsets(DECOMPOSED, vec![(0, 2)]),
+            scalar_to_byte_offsets(DECOMPOSED, vec![(0, 2)]).unwrap(),
             vec![(0, 3)]
         );
     }
 
+    #[test]
+    fn test_scalar_to_byte_offsets_invalid() {
+        assert!(scalar_to_byte_offsets("foo", vec![]).is_err());
+        assert!(scalar_to_byte_offsets("foo", vec![(0, 2)]).is_err());
+        assert!(scalar_to_byte_offsets("foo", vec![(0, 4)]).is_err());
+        assert!(scalar_to_byte_offsets("foo", vec![(2, 1), (1, 3)]).is_err());
+    }
+
     #[test]
     fn test_unicode_scalar_bounds() {
         assert_eq!(unicode_scalar_bounds("foo"), vec![1, 2, 3]);
@@ -96,6 +195,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_offsets_to_bounds() {
+        assert_eq!(offsets_to_bounds(&[(0, 3)]), Vec::<usize>::new());
+        assert_eq!(offsets_to_bounds(&[(0, 3), (3, 6), (6, 6)]), vec![3]);
+        assert_eq!(bounds_to_offsets(&[3], 6), vec![(0, 3), (3, 6)]);
+        assert_eq!(bounds_to_offsets(&[], 6), vec![(0, 6)]);
+    }
+
+    #[test]
+    fn test_normalized_to_original_offsets_identity() {
+        let normalized = NormalizedString::from("afghánských");
+
+        assert_eq!(
+            normalized_to_original_offsets(&normalized, vec![(0, 11), (11, 13), (13, 13)]),
+            vec![(0, 11), (11, 13)]
+        );
+    }
+
+    #[test]
+    fn test_normalized_to_original_offsets_composed() {
+        let mut normalized = NormalizedString::from("L\u{0065}\u{0301}l");
+
+        normalized.nfc().lowercase();
+
+        assert_eq!(normalized.get(), "l\u{00E9}l");
+
+        // l|é|l
+        assert_eq!(
+            normalized_to_original_offsets(&normalized, vec![(0, 1), (1, 3), (3, 4)]),
+            vec![(0, 1), (1, 4), (4, 5)]
+        );
+    }
+
+    #[test]
+    fn test_normalized_to_original_offsets_decomposed() {
+        let mut normalized = NormalizedString::from("l\u{00E9}l");
+
+        normalized.nfd();
+
+        // le|\u{0301}l -> l|él
+        assert_eq!(
+            normalized_to_original_offsets(&normalized, vec![(0, 2), (2, 5)]),
+            vec![(0, 1), (1, 4)]
+        );
+    }
+
     #[test]
     fn test_collect_scalar_offsets() {
         assert_eq!(
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+use serde::{Deserialize, Serialize};
+
+// segment lengths are given in trie order
+// the stem is the segment at the root of the trie and the suffix is the last segment of the path
+// for reversed tries the stem is the end of the word and the suffix its beginning
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitCriterion {
+    // minimum number of branches at a node, interior terminations count as a branch
+    pub min_children: usize,
+    pub min_stem_length: usize,
+    pub min_suffix_length: usize,
+    // boundaries closest to the root are kept
+    pub max_boundaries: Option<usize>,
+}
+
+impl Default for SplitCriterion {
+    fn default() -> Self {
+        SplitCriterion {
+            min_children: 2,
+            min_stem_length: 0,
+            min_suffix_length: 0,
+            max_boundaries: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    terminal: bool,
+}
+
+// character trie with nodes stored in insertion order, the root is at index 0
+#[derive(Clone, Debug)]
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Trie {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+
+    pub fn insert<I>(&mut self, word: I)
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut node = 0;
+
+        for c in word {
+            node = match self.nodes[node].children.get(&c) {
+                Some(child) => *child,
+                None => {
+                    self.nodes.push(TrieNode::default());
+
+                    let child = self.nodes.len() - 1;
+
+                    self.nodes[node].children.insert(c, child);
+
+                    child
+                }
+            };
+        }
+
+        self.nodes[node].terminal = true;
+    }
+
+    // number of children plus one for words terminating at the node
+    fn branches(&self, node: usize) -> usize {
+        self.nodes[node].children.len() + self.nodes[node].terminal as usize
+    }
+
+    // splits the path after every node meeting the criterion
+    // the complete path needs to be present in the trie
+    pub fn split_path(
+        &self,
+        path: &[char],
+        criterion: &SplitCriterion,
+    ) -> Result<Vec<(usize, usize)>, Error> {
+        let mut offsets: Vec<(usize, usize)> = vec![];
+
+        let mut node = 0;
+        let mut start = 0;
+
+        for (i, c) in path.iter().enumerate() {
+            node = match self.nodes[node].children.get(c) {
+                Some(child) => *child,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("{:?} not found in trie at position {}", path, i),
+                    ))
+                }
+            };
+
+            let position = i + 1;
+
+            if position == path.len() {
+                break;
+            }
+
+            if self.branches(node) >= criterion.min_children
+                && position >= criterion.min_stem_length
+                && path.len() - position >= criterion.min_suffix_length
+                && criterion
+                    .max_boundaries
+                    .is_none_or(|max| offsets.len() < max)
+            {
+                offsets.push((start, position));
+
+                start = position;
+            }
+        }
+
+        offsets.push((start, path.len()));
+
+        Ok(offsets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let mut trie = Trie::new();
+
+        trie.insert("foobar".chars());
+        trie.insert("foobaz".chars());
+        trie.insert("foo".chars());
+
+        let path = "foobar".chars().collect::<Vec<char>>();
+
+        assert_eq!(
+            trie.split_path(&path, &SplitCriterion::default()).unwrap(),
+            vec![(0, 3), (3, 5), (5, 6)]
+        );
+
+        // no node has three branches
+        let criterion = SplitCriterion {
+            min_children: 3,
+            ..SplitCriterion::default()
+        };
+
+        assert_eq!(trie.split_path(&path, &criterion).unwrap(), vec![(0, 6)]);
+    }
+
+    #[test]
+    fn test_split_missing_path() {
+        let mut trie = Trie::new();
+
+        trie.insert("foo".chars());
+
+        let criterion = SplitCriterion::default();
+
+        assert!(trie
+            .split_path(&"bar".chars().collect::<Vec<char>>(), &criterion)
+            .is_err());
+        assert!(trie
+            .split_path(&"foobar".chars().collect::<Vec<char>>(), &criterion)
+            .is_err());
+    }
+}